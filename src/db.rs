@@ -1,2 +1,3 @@
 pub mod db_operations;
 mod db_utils;
+pub mod policy;
@@ -1,8 +1,30 @@
-use surrealdb::{engine::remote::ws::Client, Surreal};
+use std::collections::{BTreeMap, HashMap};
 
-use crate::{Channel, DataBaseConfig};
+use surrealdb::{
+    engine::remote::ws::Client,
+    sql::{Datetime, Duration},
+    Surreal,
+};
 
-use super::db_utils::*;
+use crate::{
+    error::{ApiError, ValidationError},
+    response::{
+        BanListResponse, ChannelCounts, GlobalBanEntry, Page, Pagination, RelationEntry,
+        Relationship,
+    },
+    username::{normalize_username, username_skeleton, validate_username},
+    utils::api_config,
+    AcceptFollows, BanDetails, BanList, BanScope, Channel, ChannelSettings, DataBaseConfig,
+    FollowOutcome, GlobalBan, Relation, UsernameChange,
+};
+
+use super::{
+    db_utils::*,
+    policy::{check_policies, Action},
+};
+
+const DEFAULT_PAGE_LIMIT: usize = 50;
+const MAX_PAGE_LIMIT: usize = 100;
 
 pub async fn connect(database_config: &DataBaseConfig) -> Option<Surreal<Client>> {
     establish_connection(
@@ -15,11 +37,140 @@ pub async fn connect(database_config: &DataBaseConfig) -> Option<Surreal<Client>
     .await
 }
 
+/// Runs the one-time migrations newer than the stored schema version, each
+/// one bumps the version once it's done. Errors tell what stopped the
+/// migration.
+pub async fn migrate(db: &Surreal<Client>) -> Result<(), String> {
+    let version = search_schema_version(db)
+        .await
+        .ok_or("Search Schema Version Failed")?;
+    if version < 1 {
+        backfill_counts(db).await.ok_or("Backfill Counts Failed")?;
+        set_schema_version(1, db).await?;
+    }
+    if version < 2 {
+        define_username_index(db)
+            .await
+            .ok_or("Define Username Index Failed")?;
+        set_schema_version(2, db).await?;
+    }
+    if version < 3 {
+        backfill_timestamps(db)
+            .await
+            .ok_or("Backfill Timestamps Failed")?;
+        set_schema_version(3, db).await?;
+    }
+    if version < 4 {
+        backfill_normalized_usernames(db).await?;
+        set_schema_version(4, db).await?;
+    }
+    Ok(())
+}
+
+async fn set_schema_version(version: u32, db: &Surreal<Client>) -> Result<(), String> {
+    update_schema_version(version, db)
+        .await
+        .map(|_| ())
+        .ok_or(format!("Update Schema Version To {} Failed", version))
+}
+
 pub async fn create(username: &String, db: &Surreal<Client>) -> Option<Channel> {
-    match create_channel(username, db).await.pop() {
-        Some(channel) => channel,
-        None => None,
+    try_create(username, db).await.ok()
+}
+
+pub async fn try_create(username: &String, db: &Surreal<Client>) -> Result<Channel, ApiError> {
+    validate_username(username, &api_config().username_rules)?;
+    check_confusable(username, None, db).await?;
+    check_quarantine(username, None, db).await?;
+    match search_channel_by_username(username, db).await {
+        Some(_) => {
+            eprintln!("Error: Create | Channel Already Exists");
+            Err(ApiError::ChannelAlreadyExists)
+        }
+        None => create_channel(username, db)
+            .await
+            .pop()
+            .unwrap_or_default()
+            .ok_or(ApiError::Database),
+    }
+}
+
+/// Rejects look-alikes of other channels when `confusable_detection` is on.
+async fn check_confusable(
+    username: &String,
+    channel: Option<&Channel>,
+    db: &Surreal<Client>,
+) -> Result<(), ApiError> {
+    if !api_config().confusable_detection {
+        return Ok(());
+    }
+    let confusables = search_channels_by_skeleton(&username_skeleton(username), db).await;
+    match confusables
+        .iter()
+        .any(|confusable| channel.map(|channel| &channel.id) != Some(&confusable.id))
+    {
+        true => {
+            eprintln!("Error: Username Confusable | {}", username);
+            Err(ApiError::UsernameConfusable)
+        }
+        false => Ok(()),
+    }
+}
+
+/// Rejects usernames another channel gave up less than
+/// `username_quarantine_seconds` ago.
+async fn check_quarantine(
+    username: &String,
+    channel: Option<&Channel>,
+    db: &Surreal<Client>,
+) -> Result<(), ApiError> {
+    let quarantine = Duration::from_secs(api_config().username_quarantine_seconds);
+    let normalized_username = normalize_username(username);
+    for previous_owner in search_channels_by_previous_username(username, db).await {
+        if channel.map(|channel| &channel.id) == Some(&previous_owner.id) {
+            continue;
+        }
+        if let Some(last_used) = last_used(&previous_owner, &normalized_username) {
+            if quarantine + last_used > Datetime::default() {
+                eprintln!("Error: Username Quarantined | {}", username);
+                return Err(ApiError::UsernameQuarantined);
+            }
+        }
     }
+    Ok(())
+}
+
+fn check_rename_cooldown(channel: &Channel) -> Result<(), ApiError> {
+    let cooldown = Duration::from_secs(api_config().rename_cooldown_seconds);
+    match channel.username_history.last() {
+        Some(change) if cooldown + change.changed_at.clone() > Datetime::default() => {
+            eprintln!("Error: Rename Cooldown | {}", channel.username);
+            Err(ApiError::RenameCooldown)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Looks `username` up among current usernames, then among previous ones.
+/// The flag tells whether the channel was found by a previous username.
+pub async fn resolve_username(username: &String, db: &Surreal<Client>) -> Option<(Channel, bool)> {
+    match search_channel_by_username(username, db).await {
+        Some(channel) => Some((channel, false)),
+        None => search_channels_by_previous_username(username, db)
+            .await
+            .into_iter()
+            .next()
+            .map(|channel| (channel, true)),
+    }
+}
+
+pub async fn username_history(
+    username: &String,
+    db: &Surreal<Client>,
+) -> Option<Vec<UsernameChange>> {
+    search_channel_by_username(username, db)
+        .await
+        .map(|channel| channel.username_history)
 }
 
 pub async fn search_username(username: &String, db: &Surreal<Client>) -> Option<Channel> {
@@ -30,24 +181,69 @@ pub async fn search_id(id: &String, db: &Surreal<Client>) -> Option<Channel> {
     search_channel_by_id(&id.into(), db).await
 }
 
+pub async fn counts(username: &String, db: &Surreal<Client>) -> Option<ChannelCounts> {
+    search_channel_counts_by_username(username, db).await
+}
+
 pub async fn delete(username: &String, db: &Surreal<Client>) -> Option<Channel> {
+    try_delete(username, db).await.ok()
+}
+
+pub async fn try_delete(username: &String, db: &Surreal<Client>) -> Result<Channel, ApiError> {
     match search_channel_by_username(username, db).await {
-        Some(channel) => match remove_all_followers(channel.clone(), db).await {
-            Some(_) => match remove_all_followed(channel.clone(), db).await {
-                Some(_) => match remove_all_banned(channel.clone(), db).await {
-                    Some(_) => match remove_all_banned_from(channel, db).await {
-                        Some(_) => delete_channel(username, db).await,
-                        None => None,
-                    },
-                    None => None,
-                },
-                None => None,
-            },
-            None => None,
-        },
+        Some(channel) => {
+            remove_all_followers(channel.clone(), db)
+                .await
+                .ok_or(ApiError::Database)?;
+            remove_all_followed(channel.clone(), db)
+                .await
+                .ok_or(ApiError::Database)?;
+            remove_all_banned(channel.clone(), db)
+                .await
+                .ok_or(ApiError::Database)?;
+            remove_all_banned_from(channel.clone(), db)
+                .await
+                .ok_or(ApiError::Database)?;
+            remove_all_muted(channel.clone(), db)
+                .await
+                .ok_or(ApiError::Database)?;
+            remove_all_muted_from(channel.clone(), db)
+                .await
+                .ok_or(ApiError::Database)?;
+            remove_all_blocked(channel.clone(), db)
+                .await
+                .ok_or(ApiError::Database)?;
+            remove_all_blocked_from(channel.clone(), db)
+                .await
+                .ok_or(ApiError::Database)?;
+            remove_all_moderators(channel.clone(), db)
+                .await
+                .ok_or(ApiError::Database)?;
+            remove_all_moderating(channel.clone(), db)
+                .await
+                .ok_or(ApiError::Database)?;
+            remove_all_members(channel.clone(), db)
+                .await
+                .ok_or(ApiError::Database)?;
+            remove_all_organizations(channel.clone(), db)
+                .await
+                .ok_or(ApiError::Database)?;
+            delete_all_follow_requests(&channel, db)
+                .await
+                .ok_or(ApiError::Database)?;
+            for ban_list in search_ban_lists_by_owner(&channel, db).await {
+                delete_ban_list_record(&ban_list, db)
+                    .await
+                    .ok_or(ApiError::Database)?;
+            }
+            remove_from_all_ban_lists(&channel, db)
+                .await
+                .ok_or(ApiError::Database)?;
+            delete_channel(username, db).await.ok_or(ApiError::Database)
+        }
         None => {
             eprintln!("Error: Delete | Channel Not Exists");
-            None
+            Err(ApiError::ChannelNotExists)
         }
     }
 }
@@ -57,22 +253,284 @@ pub async fn change_username(
     username: &String,
     db: &Surreal<Client>,
 ) -> Option<Channel> {
+    try_change_username(updated_username, username, db)
+        .await
+        .ok()
+}
+
+pub async fn try_change_username(
+    updated_username: &String,
+    username: &String,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    validate_username(updated_username, &api_config().username_rules)?;
+    match search_channel_by_username(username, db).await {
+        Some(mut channel) => match search_channel_by_username(updated_username, db).await {
+            Some(existing) if existing.id != channel.id => {
+                eprintln!("Error: Update Username | Username Already Exists");
+                Err(ApiError::ChannelAlreadyExists)
+            }
+            _ => {
+                check_rename_cooldown(&channel)?;
+                check_confusable(updated_username, Some(&channel), db).await?;
+                check_quarantine(updated_username, Some(&channel), db).await?;
+                channel.username_history.push(UsernameChange {
+                    normalized_username: normalize_username(&channel.username),
+                    username: channel.username,
+                    changed_at: Datetime::default(),
+                });
+                channel.username = updated_username.to_string();
+                update_channel(channel, db).await.ok_or(ApiError::Database)
+            }
+        },
+        None => {
+            eprintln!("Error: Update Username");
+            Err(ApiError::ChannelNotExists)
+        }
+    }
+}
+
+pub async fn update_profile(
+    username: &String,
+    display_name: Option<String>,
+    metadata: Option<BTreeMap<String, String>>,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
     match search_channel_by_username(username, db).await {
         Some(mut channel) => {
-            channel.username = updated_username.to_string();
-            update_channel(channel, db).await
+            if let Some(display_name) = display_name {
+                channel.display_name = match display_name.trim().is_empty() {
+                    true => None,
+                    false => Some(display_name),
+                };
+            }
+            if let Some(metadata) = metadata {
+                channel.metadata = metadata;
+            }
+            update_channel(channel, db).await.ok_or(ApiError::Database)
         }
         None => {
-            eprintln!("Error: Update Username");
-            None
+            eprintln!("Error: Update Profile | Channel Not Exists");
+            Err(ApiError::ChannelNotExists)
+        }
+    }
+}
+
+pub async fn settings(username: &String, db: &Surreal<Client>) -> Option<ChannelSettings> {
+    search_channel_by_username(username, db)
+        .await
+        .map(|channel| channel.settings)
+}
+
+/// Only the given settings are changed.
+pub async fn update_settings(
+    username: &String,
+    accept_follows: Option<AcceptFollows>,
+    hide_follower_list: Option<bool>,
+    hide_following_list: Option<bool>,
+    bans_visible_to_victim: Option<bool>,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    match search_channel_by_username(username, db).await {
+        Some(mut channel) => {
+            let settings = &mut channel.settings;
+            if let Some(accept_follows) = accept_follows {
+                settings.accept_follows = accept_follows;
+            }
+            if let Some(hide_follower_list) = hide_follower_list {
+                settings.hide_follower_list = hide_follower_list;
+            }
+            if let Some(hide_following_list) = hide_following_list {
+                settings.hide_following_list = hide_following_list;
+            }
+            if let Some(bans_visible_to_victim) = bans_visible_to_victim {
+                settings.bans_visible_to_victim = bans_visible_to_victim;
+            }
+            update_channel(channel, db).await.ok_or(ApiError::Database)
+        }
+        None => {
+            eprintln!("Error: Update Settings | Channel Not Exists");
+            Err(ApiError::ChannelNotExists)
         }
     }
 }
 
 pub async fn follow(follower: &String, followed: &String, db: &Surreal<Client>) -> Option<Channel> {
+    try_follow(follower, followed, None, db)
+        .await
+        .ok()
+        .map(FollowOutcome::channel)
+}
+
+/// `source` tells where the follow came from, e.g. `search` or `raid`.
+///
+/// Following a channel accepting follows on approval only creates a request,
+/// the follow happens once the channel approves it.
+pub async fn try_follow(
+    follower: &String,
+    followed: &String,
+    source: Option<String>,
+    db: &Surreal<Client>,
+) -> Result<FollowOutcome, ApiError> {
+    let (follower_channel, followed_channel) =
+        search_channel_pair_by_username(follower, followed, db)
+            .await
+            .ok_or(ApiError::ChannelNotExists)?;
+    if check_follow(&follower_channel, &followed_channel, db).await? {
+        create_follow_request(new_follow(&follower_channel, &followed_channel, source), db)
+            .await
+            .ok_or(ApiError::Database)?;
+        return Ok(FollowOutcome::Requested(follower_channel));
+    }
+    add_follow(
+        follower,
+        followed,
+        &follower_channel,
+        &followed_channel,
+        source,
+        db,
+    )
+    .await
+    .map(FollowOutcome::Followed)
+}
+
+/// Checks of `try_follow` and `batch_follow`, answers whether `followed`
+/// accepts follows on approval so the follow has to be requested.
+async fn check_follow(
+    follower: &Channel,
+    followed: &Channel,
+    db: &Surreal<Client>,
+) -> Result<bool, ApiError> {
+    check_relation(follower, Relation::Followed, followed, true)?;
+    check_policies(Action::Follow, follower, followed, db).await?;
+    if followed.settings.accept_follows != AcceptFollows::Approval {
+        return Ok(false);
+    }
+    match search_follow_request(follower, followed, db).await {
+        Some(_) => {
+            eprintln!("Error: Follow | Already Requested");
+            Err(ApiError::AlreadyRequested)
+        }
+        None => Ok(true),
+    }
+}
+
+async fn add_follow(
+    follower: &String,
+    followed: &String,
+    follower_channel: &Channel,
+    followed_channel: &Channel,
+    source: Option<String>,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
     match add_follower(follower, followed, db).await {
-        Some(_) => add_followed(followed, follower, db).await,
-        None => None,
+        Some(_) => match add_followed(followed, follower, db).await {
+            Some(channel) => {
+                create_follow(new_follow(follower_channel, followed_channel, source), db)
+                    .await
+                    .ok_or(ApiError::Database)?;
+                Ok(channel)
+            }
+            None => Err(ApiError::Database),
+        },
+        None => Err(ApiError::Database),
+    }
+}
+
+pub async fn approve_follow_request(
+    follower: &String,
+    followed: &String,
+    db: &Surreal<Client>,
+) -> Option<Channel> {
+    try_approve_follow_request(follower, followed, db)
+        .await
+        .ok()
+}
+
+/// Performs the requested follow, the rules of `follow` are checked again
+/// since they may have changed while the request waited.
+pub async fn try_approve_follow_request(
+    follower: &String,
+    followed: &String,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    let (follower_channel, followed_channel) =
+        search_channel_pair_by_username(follower, followed, db)
+            .await
+            .ok_or(ApiError::ChannelNotExists)?;
+    let request = search_follow_request(&follower_channel, &followed_channel, db)
+        .await
+        .ok_or(ApiError::NotRequested)?;
+    check_relation(
+        &follower_channel,
+        Relation::Followed,
+        &followed_channel,
+        true,
+    )?;
+    check_policies(Action::Follow, &follower_channel, &followed_channel, db).await?;
+    let channel = add_follow(
+        follower,
+        followed,
+        &follower_channel,
+        &followed_channel,
+        request.source,
+        db,
+    )
+    .await?;
+    delete_follow_request(&follower_channel, &followed_channel, db)
+        .await
+        .ok_or(ApiError::Database)?;
+    Ok(channel)
+}
+
+pub async fn reject_follow_request(
+    follower: &String,
+    followed: &String,
+    db: &Surreal<Client>,
+) -> Option<Channel> {
+    try_remove_follow_request(follower, followed, db).await.ok()
+}
+
+pub async fn cancel_follow_request(
+    follower: &String,
+    followed: &String,
+    db: &Surreal<Client>,
+) -> Option<Channel> {
+    try_remove_follow_request(follower, followed, db).await.ok()
+}
+
+/// Rejecting and cancelling both drop the request, returns the follower.
+pub async fn try_remove_follow_request(
+    follower: &String,
+    followed: &String,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    let (follower_channel, followed_channel) =
+        search_channel_pair_by_username(follower, followed, db)
+            .await
+            .ok_or(ApiError::ChannelNotExists)?;
+    match delete_follow_request(&follower_channel, &followed_channel, db).await {
+        Some(_) => Ok(follower_channel),
+        None => Err(ApiError::NotRequested),
+    }
+}
+
+pub async fn list_follow_requests(
+    username: &String,
+    pagination: &Pagination,
+    db: &Surreal<Client>,
+) -> Option<Page<RelationEntry>> {
+    match search_channel_by_username(username, db).await {
+        Some(channel) => {
+            let candidates = follow_request_candidates(&channel, db).await;
+            list_page(candidates, pagination, false, None, db)
+                .await
+                .ok()
+        }
+        None => {
+            eprintln!("Error: List Follow Requests | Channel Not Exists");
+            None
+        }
     }
 }
 
@@ -81,30 +539,1398 @@ pub async fn unfollow(
     followed: &String,
     db: &Surreal<Client>,
 ) -> Option<Channel> {
+    try_unfollow(follower, followed, db).await.ok()
+}
+
+pub async fn try_unfollow(
+    follower: &String,
+    followed: &String,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    let (follower_channel, followed_channel) =
+        search_channel_pair_by_username(follower, followed, db)
+            .await
+            .ok_or(ApiError::ChannelNotExists)?;
+    check_relation(
+        &follower_channel,
+        Relation::Followed,
+        &followed_channel,
+        false,
+    )?;
     match remove_follower(follower, followed, db).await {
-        Some(_) => remove_followed(followed, follower, db).await,
-        None => None,
+        Some(_) => match remove_followed(followed, follower, db).await {
+            Some(channel) => {
+                delete_follow(&follower_channel, &followed_channel, db).await;
+                Ok(channel)
+            }
+            None => Err(ApiError::Database),
+        },
+        None => Err(ApiError::Database),
     }
 }
 
 pub async fn ban(victim: &String, judge: &String, db: &Surreal<Client>) -> Option<Channel> {
+    try_ban(victim, judge, BanDetails::default(), db).await.ok()
+}
+
+/// Bans are recorded with `details`, which must hold a reason when
+/// `require_ban_reason` is set.
+pub async fn try_ban(
+    victim: &String,
+    judge: &String,
+    details: BanDetails,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    check_ban_reason(&details)?;
+    let (judge_channel, victim_channel) = search_channel_pair_by_username(judge, victim, db)
+        .await
+        .ok_or(ApiError::ChannelNotExists)?;
+    let (judge_channel, victim_channel) =
+        match check_ban(&judge_channel, &victim_channel, db).await? {
+            true => {
+                try_unban(victim, judge, db).await?;
+                search_channel_pair_by_username(judge, victim, db)
+                    .await
+                    .ok_or(ApiError::ChannelNotExists)?
+            }
+            false => (judge_channel, victim_channel),
+        };
+    delete_follow_request(&victim_channel, &judge_channel, db).await;
+    if api_config().ban_removes_follow
+        && has_relation(&victim_channel, Relation::Followed, &judge_channel)
+        && fully_banned_with(&victim_channel, &judge_channel, details.scope, db).await
+    {
+        try_unfollow(victim, judge, db).await?;
+    }
     match add_banned(victim, judge, db).await {
-        Some(_) => add_banned_from(judge, victim, db).await,
-        None => None,
+        Some(_) => match add_banned_from(judge, victim, db).await {
+            Some(channel) => {
+                create_ban(new_ban(&victim_channel, &judge_channel, &details), db)
+                    .await
+                    .ok_or(ApiError::Database)?;
+                Ok(channel)
+            }
+            None => Err(ApiError::Database),
+        },
+        None => Err(ApiError::Database),
+    }
+}
+
+/// Whether a ban of `scope` leaves `victim` fully banned by `judge`, a chat
+/// ban only removes the follow when another ban already covers everything.
+async fn fully_banned_with(
+    victim: &Channel,
+    judge: &Channel,
+    scope: BanScope,
+    db: &Surreal<Client>,
+) -> bool {
+    scope == BanScope::Full || effective_ban_scope(victim, judge, db).await == Some(BanScope::Full)
+}
+
+/// Checks of `try_ban` and `batch_ban`, answers whether `judge` still lists
+/// an expired ban of `victim` that the new ban replaces.
+async fn check_ban(
+    judge: &Channel,
+    victim: &Channel,
+    db: &Surreal<Client>,
+) -> Result<bool, ApiError> {
+    let expired = has_relation(judge, Relation::Banned, victim)
+        && search_ban(victim, judge, db)
+            .await
+            .is_some_and(|ban| ban.is_expired());
+    if !expired {
+        check_relation(judge, Relation::Banned, victim, true)?;
+    }
+    check_policies(Action::Ban, judge, victim, db).await?;
+    Ok(expired)
+}
+
+/// Lifts every expired ban the same way `unban` does, returns how many were
+/// lifted.
+pub async fn remove_expired_bans(db: &Surreal<Client>) -> usize {
+    let mut removed = 0;
+    for ban in search_expired_bans(db).await {
+        let victim = search_channel_by_id(&ban.victim.id, db).await;
+        let judge = search_channel_by_id(&ban.judge.id, db).await;
+        match (victim, judge) {
+            (Some(victim), Some(judge)) => {
+                if try_unban(&victim.username, &judge.username, db)
+                    .await
+                    .is_ok()
+                {
+                    removed += 1;
+                }
+            }
+            _ => {
+                delete_ban_record(&ban, db).await;
+            }
+        }
+    }
+    removed
+}
+
+fn check_ban_reason(details: &BanDetails) -> Result<(), ApiError> {
+    match api_config().require_ban_reason && details.reason.is_none() {
+        true => Err(ApiError::Validation(ValidationError {
+            field: "reason",
+            code: "ban_reason_required",
+            message: "Ban Reason Required".to_string(),
+        })),
+        false => Ok(()),
     }
 }
 
 pub async fn unban(victim: &String, judge: &String, db: &Surreal<Client>) -> Option<Channel> {
+    try_unban(victim, judge, db).await.ok()
+}
+
+pub async fn try_unban(
+    victim: &String,
+    judge: &String,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    let (judge_channel, victim_channel) = search_channel_pair_by_username(judge, victim, db)
+        .await
+        .ok_or(ApiError::ChannelNotExists)?;
+    check_relation(&judge_channel, Relation::Banned, &victim_channel, false)?;
     match remove_banned(victim, judge, db).await {
-        Some(_) => remove_banned_from(judge, victim, db).await,
-        None => None,
+        Some(_) => match remove_banned_from(judge, victim, db).await {
+            Some(channel) => {
+                delete_ban(&victim_channel, &judge_channel, db).await;
+                Ok(channel)
+            }
+            None => Err(ApiError::Database),
+        },
+        None => Err(ApiError::Database),
+    }
+}
+
+pub async fn mute(victim: &String, judge: &String, db: &Surreal<Client>) -> Option<Channel> {
+    try_mute(victim, judge, None, db).await.ok()
+}
+
+/// Muted channels can still watch but can't chat, the mute lifts itself
+/// after `duration_seconds` when given.
+pub async fn try_mute(
+    victim: &String,
+    judge: &String,
+    duration_seconds: Option<u64>,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    apply_mute(victim, judge, duration_seconds, None, db).await
+}
+
+async fn apply_mute(
+    victim: &String,
+    judge: &String,
+    duration_seconds: Option<u64>,
+    moderator: Option<String>,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    let (judge_channel, victim_channel) = search_channel_pair_by_username(judge, victim, db)
+        .await
+        .ok_or(ApiError::ChannelNotExists)?;
+    let expired = search_mute(&victim_channel, &judge_channel, db)
+        .await
+        .is_some_and(|mute| mute.is_expired());
+    let (judge_channel, victim_channel) =
+        match expired && has_relation(&judge_channel, Relation::Muted, &victim_channel) {
+            true => {
+                try_unmute(victim, judge, db).await?;
+                search_channel_pair_by_username(judge, victim, db)
+                    .await
+                    .ok_or(ApiError::ChannelNotExists)?
+            }
+            false => (judge_channel, victim_channel),
+        };
+    check_relation(&judge_channel, Relation::Muted, &victim_channel, true)?;
+    check_policies(Action::Mute, &judge_channel, &victim_channel, db).await?;
+    match add_relation(victim, judge, Relation::Muted, db).await {
+        Some(_) => match add_relation(judge, victim, Relation::MutedFrom, db).await {
+            Some(channel) => {
+                create_mute(
+                    new_mute(&victim_channel, &judge_channel, duration_seconds, moderator),
+                    db,
+                )
+                .await
+                .ok_or(ApiError::Database)?;
+                Ok(channel)
+            }
+            None => Err(ApiError::Database),
+        },
+        None => Err(ApiError::Database),
+    }
+}
+
+pub async fn unmute(victim: &String, judge: &String, db: &Surreal<Client>) -> Option<Channel> {
+    try_unmute(victim, judge, db).await.ok()
+}
+
+pub async fn try_unmute(
+    victim: &String,
+    judge: &String,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    let (judge_channel, victim_channel) = search_channel_pair_by_username(judge, victim, db)
+        .await
+        .ok_or(ApiError::ChannelNotExists)?;
+    check_relation(&judge_channel, Relation::Muted, &victim_channel, false)?;
+    match remove_relation(victim, judge, Relation::Muted, db).await {
+        Some(_) => match remove_relation(judge, victim, Relation::MutedFrom, db).await {
+            Some(channel) => {
+                delete_mute(&victim_channel, &judge_channel, db).await;
+                Ok(channel)
+            }
+            None => Err(ApiError::Database),
+        },
+        None => Err(ApiError::Database),
+    }
+}
+
+/// Lifts every expired mute the same way `unmute` does, returns how many
+/// were lifted.
+pub async fn remove_expired_mutes(db: &Surreal<Client>) -> usize {
+    let mut removed = 0;
+    for mute in search_expired_mutes(db).await {
+        let victim = search_channel_by_id(&mute.victim.id, db).await;
+        let judge = search_channel_by_id(&mute.judge.id, db).await;
+        match (victim, judge) {
+            (Some(victim), Some(judge)) => {
+                if try_unmute(&victim.username, &judge.username, db)
+                    .await
+                    .is_ok()
+                {
+                    removed += 1;
+                }
+            }
+            _ => {
+                delete_mute_record(&mute, db).await;
+            }
+        }
+    }
+    removed
+}
+
+pub async fn is_muted(victim: &String, judge: &String, db: &Surreal<Client>) -> bool {
+    match search_channel_pair_by_username(judge, victim, db).await {
+        Some((judge_channel, victim_channel)) => {
+            has_relation(&judge_channel, Relation::Muted, &victim_channel)
+                && !search_mute(&victim_channel, &judge_channel, db)
+                    .await
+                    .is_some_and(|mute| mute.is_expired())
+        }
+        None => {
+            eprintln!("Error: Can't Check Is Muted | Channel Not Exists");
+            false
+        }
+    }
+}
+
+pub async fn block(blocker: &String, blocked: &String, db: &Surreal<Client>) -> Option<Channel> {
+    try_block(blocker, blocked, db).await.ok()
+}
+
+/// Removes follows in both directions, neither side can follow the other
+/// until the block is lifted.
+pub async fn try_block(
+    blocker: &String,
+    blocked: &String,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    let (blocker_channel, blocked_channel) = search_channel_pair_by_username(blocker, blocked, db)
+        .await
+        .ok_or(ApiError::ChannelNotExists)?;
+    check_relation(&blocker_channel, Relation::Blocked, &blocked_channel, true)?;
+    check_policies(Action::Block, &blocker_channel, &blocked_channel, db).await?;
+    delete_follow_request(&blocker_channel, &blocked_channel, db).await;
+    delete_follow_request(&blocked_channel, &blocker_channel, db).await;
+    if has_relation(&blocker_channel, Relation::Followed, &blocked_channel) {
+        try_unfollow(blocker, blocked, db).await?;
+    }
+    if has_relation(&blocker_channel, Relation::Follower, &blocked_channel) {
+        try_unfollow(blocked, blocker, db).await?;
+    }
+    match add_relation(blocker, blocked, Relation::BlockedFrom, db).await {
+        Some(_) => add_relation(blocked, blocker, Relation::Blocked, db)
+            .await
+            .ok_or(ApiError::Database),
+        None => Err(ApiError::Database),
+    }
+}
+
+pub async fn unblock(blocker: &String, blocked: &String, db: &Surreal<Client>) -> Option<Channel> {
+    try_unblock(blocker, blocked, db).await.ok()
+}
+
+pub async fn try_unblock(
+    blocker: &String,
+    blocked: &String,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    let (blocker_channel, blocked_channel) = search_channel_pair_by_username(blocker, blocked, db)
+        .await
+        .ok_or(ApiError::ChannelNotExists)?;
+    check_relation(&blocker_channel, Relation::Blocked, &blocked_channel, false)?;
+    match remove_relation(blocker, blocked, Relation::BlockedFrom, db).await {
+        Some(_) => remove_relation(blocked, blocker, Relation::Blocked, db)
+            .await
+            .ok_or(ApiError::Database),
+        None => Err(ApiError::Database),
+    }
+}
+
+pub async fn is_blocked(blocked: &String, blocker: &String, db: &Surreal<Client>) -> bool {
+    match search_channel_pair_by_username(blocker, blocked, db).await {
+        Some((blocker_channel, blocked_channel)) => {
+            has_relation(&blocker_channel, Relation::Blocked, &blocked_channel)
+        }
+        None => {
+            eprintln!("Error: Can't Check Is Blocked | Channel Not Exists");
+            false
+        }
+    }
+}
+
+pub async fn add_moderator(
+    moderator: &String,
+    channel: &String,
+    db: &Surreal<Client>,
+) -> Option<Channel> {
+    try_add_moderator(moderator, channel, db).await.ok()
+}
+
+/// Moderators can ban, unban, mute and unmute on behalf of `channel`,
+/// returns the channel.
+pub async fn try_add_moderator(
+    moderator: &String,
+    channel: &String,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    let (channel_channel, moderator_channel) =
+        search_channel_pair_by_username(channel, moderator, db)
+            .await
+            .ok_or(ApiError::ChannelNotExists)?;
+    check_relation(
+        &channel_channel,
+        Relation::Moderator,
+        &moderator_channel,
+        true,
+    )?;
+    check_policies(Action::Moderate, &channel_channel, &moderator_channel, db).await?;
+    match add_relation(channel, moderator, Relation::Moderating, db).await {
+        Some(_) => add_relation(moderator, channel, Relation::Moderator, db)
+            .await
+            .ok_or(ApiError::Database),
+        None => Err(ApiError::Database),
+    }
+}
+
+pub async fn remove_moderator(
+    moderator: &String,
+    channel: &String,
+    db: &Surreal<Client>,
+) -> Option<Channel> {
+    try_remove_moderator(moderator, channel, db).await.ok()
+}
+
+pub async fn try_remove_moderator(
+    moderator: &String,
+    channel: &String,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    let (channel_channel, moderator_channel) =
+        search_channel_pair_by_username(channel, moderator, db)
+            .await
+            .ok_or(ApiError::ChannelNotExists)?;
+    check_relation(
+        &channel_channel,
+        Relation::Moderator,
+        &moderator_channel,
+        false,
+    )?;
+    match remove_relation(channel, moderator, Relation::Moderating, db).await {
+        Some(_) => remove_relation(moderator, channel, Relation::Moderator, db)
+            .await
+            .ok_or(ApiError::Database),
+        None => Err(ApiError::Database),
+    }
+}
+
+pub async fn is_moderator(moderator: &String, channel: &String, db: &Surreal<Client>) -> bool {
+    match search_channel_pair_by_username(channel, moderator, db).await {
+        Some((channel_channel, moderator_channel)) => {
+            has_relation(&channel_channel, Relation::Moderator, &moderator_channel)
+        }
+        None => {
+            eprintln!("Error: Can't Check Is Moderator | Channel Not Exists");
+            false
+        }
+    }
+}
+
+pub async fn add_member(
+    member: &String,
+    organization: &String,
+    db: &Surreal<Client>,
+) -> Option<Channel> {
+    try_add_member(member, organization, db).await.ok()
+}
+
+/// Members inherit the bans of `organization`, returns the organization.
+pub async fn try_add_member(
+    member: &String,
+    organization: &String,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    let (organization_channel, member_channel) =
+        search_channel_pair_by_username(organization, member, db)
+            .await
+            .ok_or(ApiError::ChannelNotExists)?;
+    check_relation(
+        &organization_channel,
+        Relation::Member,
+        &member_channel,
+        true,
+    )?;
+    check_policies(
+        Action::AddMember,
+        &organization_channel,
+        &member_channel,
+        db,
+    )
+    .await?;
+    match add_relation(organization, member, Relation::Organization, db).await {
+        Some(_) => add_relation(member, organization, Relation::Member, db)
+            .await
+            .ok_or(ApiError::Database),
+        None => Err(ApiError::Database),
+    }
+}
+
+pub async fn remove_member(
+    member: &String,
+    organization: &String,
+    db: &Surreal<Client>,
+) -> Option<Channel> {
+    try_remove_member(member, organization, db).await.ok()
+}
+
+pub async fn try_remove_member(
+    member: &String,
+    organization: &String,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    let (organization_channel, member_channel) =
+        search_channel_pair_by_username(organization, member, db)
+            .await
+            .ok_or(ApiError::ChannelNotExists)?;
+    check_relation(
+        &organization_channel,
+        Relation::Member,
+        &member_channel,
+        false,
+    )?;
+    match remove_relation(organization, member, Relation::Organization, db).await {
+        Some(_) => remove_relation(member, organization, Relation::Member, db)
+            .await
+            .ok_or(ApiError::Database),
+        None => Err(ApiError::Database),
+    }
+}
+
+pub async fn is_member(member: &String, organization: &String, db: &Surreal<Client>) -> bool {
+    match search_channel_pair_by_username(organization, member, db).await {
+        Some((organization_channel, member_channel)) => {
+            has_relation(&organization_channel, Relation::Member, &member_channel)
+        }
+        None => {
+            eprintln!("Error: Can't Check Is Member | Channel Not Exists");
+            false
+        }
+    }
+}
+
+/// `moderator` is the judge itself or one of its moderators, returns its
+/// username to be recorded with the action.
+async fn check_moderator(
+    moderator: &String,
+    judge: &String,
+    db: &Surreal<Client>,
+) -> Result<String, ApiError> {
+    let (judge_channel, moderator_channel) = search_channel_pair_by_username(judge, moderator, db)
+        .await
+        .ok_or(ApiError::ChannelNotExists)?;
+    match judge_channel.id == moderator_channel.id
+        || has_relation(&judge_channel, Relation::Moderator, &moderator_channel)
+    {
+        true => Ok(moderator_channel.username),
+        false => {
+            eprintln!("Error: Moderator Action | Moderator Required");
+            Err(ApiError::ModeratorRequired)
+        }
     }
 }
 
+/// Bans on behalf of `judge`, recording `moderator` as the ban's moderator.
+pub async fn try_moderator_ban(
+    victim: &String,
+    judge: &String,
+    moderator: &String,
+    details: BanDetails,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    let moderator = check_moderator(moderator, judge, db).await?;
+    let details = BanDetails {
+        moderator: Some(moderator),
+        ..details
+    };
+    try_ban(victim, judge, details, db).await
+}
+
+pub async fn try_moderator_unban(
+    victim: &String,
+    judge: &String,
+    moderator: &String,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    check_moderator(moderator, judge, db).await?;
+    try_unban(victim, judge, db).await
+}
+
+/// Mutes on behalf of `judge`, recording `moderator` with the mute.
+pub async fn try_moderator_mute(
+    victim: &String,
+    judge: &String,
+    moderator: &String,
+    duration_seconds: Option<u64>,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    let moderator = check_moderator(moderator, judge, db).await?;
+    apply_mute(victim, judge, duration_seconds, Some(moderator), db).await
+}
+
+pub async fn try_moderator_unmute(
+    victim: &String,
+    judge: &String,
+    moderator: &String,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    check_moderator(moderator, judge, db).await?;
+    try_unmute(victim, judge, db).await
+}
+
 pub async fn is_follower(follower: &String, followed: &String, db: &Surreal<Client>) -> bool {
     is_follower_by_username(follower, followed, db).await
 }
 
-pub async fn is_banned(victim: &String, judge: &String, db: &Surreal<Client>) -> bool {
-    is_banned_by_username(victim, judge, db).await
+/// Effective scope of the ban of `victim` by `judge`, `None` when `victim`
+/// isn't banned or the ban doesn't cover `scope`.
+///
+/// Globally banned channels are banned from every channel, channels in a ban
+/// list the judge subscribes to are banned unless the judge allowed them and
+/// bans of the judge's organizations apply to the judge too.
+pub async fn is_banned(
+    victim: &String,
+    judge: &String,
+    scope: Option<BanScope>,
+    db: &Surreal<Client>,
+) -> Option<BanScope> {
+    let (victim_channel, judge_channel) = match (
+        search_channel_by_username(victim, db).await,
+        search_channel_by_username(judge, db).await,
+    ) {
+        (Some(victim_channel), Some(judge_channel)) => (victim_channel, judge_channel),
+        _ => {
+            eprintln!("Error: Can't Check Is Banned | Channel Not Exists");
+            return None;
+        }
+    };
+    covering_scope(
+        effective_ban_scope(&victim_channel, &judge_channel, db).await,
+        scope,
+    )
+}
+
+pub async fn is_ban_listed(victim: &String, judge: &String, db: &Surreal<Client>) -> bool {
+    match (
+        search_channel_by_username(victim, db).await,
+        search_channel_by_username(judge, db).await,
+    ) {
+        (Some(victim), Some(judge)) => is_banned_by_ban_lists(&victim, &judge, db).await,
+        _ => {
+            eprintln!("Error: Can't Check Is Ban Listed | Channel Not Exists");
+            false
+        }
+    }
+}
+
+pub async fn is_banned_by_organization(
+    victim: &String,
+    judge: &String,
+    db: &Surreal<Client>,
+) -> bool {
+    match (
+        search_channel_by_username(victim, db).await,
+        search_channel_by_username(judge, db).await,
+    ) {
+        (Some(victim), Some(judge)) => is_banned_by_organizations(&victim, &judge, db).await,
+        _ => {
+            eprintln!("Error: Can't Check Is Banned By Organization | Channel Not Exists");
+            false
+        }
+    }
+}
+
+async fn owned_ban_list(
+    owner: &String,
+    name: &String,
+    db: &Surreal<Client>,
+) -> Result<(Channel, BanList), ApiError> {
+    let owner = search_channel_by_username(owner, db)
+        .await
+        .ok_or(ApiError::ChannelNotExists)?;
+    let ban_list = search_ban_list(&owner, name, db)
+        .await
+        .ok_or(ApiError::BanListNotExists)?;
+    Ok((owner, ban_list))
+}
+
+pub async fn create_ban_list(
+    owner: &String,
+    name: &String,
+    db: &Surreal<Client>,
+) -> Option<BanListResponse> {
+    try_create_ban_list(owner, name, db).await.ok()
+}
+
+pub async fn try_create_ban_list(
+    owner: &String,
+    name: &String,
+    db: &Surreal<Client>,
+) -> Result<BanListResponse, ApiError> {
+    let owner = search_channel_by_username(owner, db)
+        .await
+        .ok_or(ApiError::ChannelNotExists)?;
+    if search_ban_list(&owner, name, db).await.is_some() {
+        eprintln!("Error: Create Ban List | Ban List Already Exists");
+        return Err(ApiError::BanListAlreadyExists);
+    }
+    let ban_list = update_ban_list(new_ban_list(&owner, name), db)
+        .await
+        .ok_or(ApiError::Database)?;
+    Ok(ban_list_response(ban_list, db).await)
+}
+
+pub async fn delete_ban_list(
+    owner: &String,
+    name: &String,
+    db: &Surreal<Client>,
+) -> Option<BanListResponse> {
+    try_delete_ban_list(owner, name, db).await.ok()
+}
+
+/// Deletes the list, subscribers stop treating its entries as banned.
+pub async fn try_delete_ban_list(
+    owner: &String,
+    name: &String,
+    db: &Surreal<Client>,
+) -> Result<BanListResponse, ApiError> {
+    let (_, ban_list) = owned_ban_list(owner, name, db).await?;
+    let thing = ban_list_thing(&ban_list.owner.id, &ban_list.name);
+    for subscriber in hydrate_list(&ban_list.subscriber_list, db).await {
+        let mut subscriber = subscriber;
+        subscriber
+            .ban_list_subscriptions
+            .retain(|subscription| subscription != &thing);
+        update_channel(subscriber, db)
+            .await
+            .ok_or(ApiError::Database)?;
+    }
+    let ban_list = delete_ban_list_record(&ban_list, db)
+        .await
+        .ok_or(ApiError::Database)?;
+    Ok(ban_list_response(ban_list, db).await)
+}
+
+pub async fn publish_ban_list(
+    owner: &String,
+    name: &String,
+    published: bool,
+    db: &Surreal<Client>,
+) -> Option<BanListResponse> {
+    try_publish_ban_list(owner, name, published, db).await.ok()
+}
+
+/// Unpublished lists keep their subscribers but only apply to the owner.
+pub async fn try_publish_ban_list(
+    owner: &String,
+    name: &String,
+    published: bool,
+    db: &Surreal<Client>,
+) -> Result<BanListResponse, ApiError> {
+    let (_, mut ban_list) = owned_ban_list(owner, name, db).await?;
+    ban_list.published = published;
+    let ban_list = update_ban_list(ban_list, db)
+        .await
+        .ok_or(ApiError::Database)?;
+    Ok(ban_list_response(ban_list, db).await)
+}
+
+pub async fn add_to_ban_list(
+    owner: &String,
+    name: &String,
+    victim: &String,
+    db: &Surreal<Client>,
+) -> Option<BanListResponse> {
+    try_add_to_ban_list(owner, name, victim, db).await.ok()
+}
+
+pub async fn try_add_to_ban_list(
+    owner: &String,
+    name: &String,
+    victim: &String,
+    db: &Surreal<Client>,
+) -> Result<BanListResponse, ApiError> {
+    let (owner, mut ban_list) = owned_ban_list(owner, name, db).await?;
+    let victim = search_channel_by_username(victim, db)
+        .await
+        .ok_or(ApiError::ChannelNotExists)?;
+    check_policies(Action::Ban, &owner, &victim, db).await?;
+    let victim_id = victim.id.ok_or(ApiError::Database)?.id;
+    if ban_list.entry_list.contains(&victim_id) {
+        eprintln!("Error: Add To Ban List | Already In Ban List");
+        return Err(ApiError::AlreadyInBanList);
+    }
+    ban_list.entry_list.push(victim_id);
+    let ban_list = update_ban_list(ban_list, db)
+        .await
+        .ok_or(ApiError::Database)?;
+    Ok(ban_list_response(ban_list, db).await)
+}
+
+pub async fn remove_from_ban_list(
+    owner: &String,
+    name: &String,
+    victim: &String,
+    db: &Surreal<Client>,
+) -> Option<BanListResponse> {
+    try_remove_from_ban_list(owner, name, victim, db).await.ok()
+}
+
+pub async fn try_remove_from_ban_list(
+    owner: &String,
+    name: &String,
+    victim: &String,
+    db: &Surreal<Client>,
+) -> Result<BanListResponse, ApiError> {
+    let (_, mut ban_list) = owned_ban_list(owner, name, db).await?;
+    let victim = search_channel_by_username(victim, db)
+        .await
+        .ok_or(ApiError::ChannelNotExists)?;
+    let victim_id = victim.id.ok_or(ApiError::Database)?.id;
+    if !ban_list.entry_list.contains(&victim_id) {
+        eprintln!("Error: Remove From Ban List | Not In Ban List");
+        return Err(ApiError::NotInBanList);
+    }
+    ban_list.entry_list.retain(|id| id != &victim_id);
+    let ban_list = update_ban_list(ban_list, db)
+        .await
+        .ok_or(ApiError::Database)?;
+    Ok(ban_list_response(ban_list, db).await)
+}
+
+pub async fn subscribe_ban_list(
+    subscriber: &String,
+    owner: &String,
+    name: &String,
+    db: &Surreal<Client>,
+) -> Option<Channel> {
+    try_subscribe_ban_list(subscriber, owner, name, db)
+        .await
+        .ok()
+}
+
+/// Only published lists can be subscribed to, except by their owner.
+pub async fn try_subscribe_ban_list(
+    subscriber: &String,
+    owner: &String,
+    name: &String,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    let mut subscriber = search_channel_by_username(subscriber, db)
+        .await
+        .ok_or(ApiError::ChannelNotExists)?;
+    let (owner, mut ban_list) = owned_ban_list(owner, name, db).await?;
+    if !ban_list.published && subscriber.id != owner.id {
+        eprintln!("Error: Subscribe Ban List | Ban List Not Published");
+        return Err(ApiError::BanListNotPublished);
+    }
+    let thing = ban_list_thing(&ban_list.owner.id, &ban_list.name);
+    if subscriber.ban_list_subscriptions.contains(&thing) {
+        eprintln!("Error: Subscribe Ban List | Already Subscribed");
+        return Err(ApiError::AlreadySubscribed);
+    }
+    let subscriber_id = subscriber.id.clone().ok_or(ApiError::Database)?.id;
+    subscriber.ban_list_subscriptions.push(thing);
+    ban_list.subscriber_list.push(subscriber_id);
+    update_ban_list(ban_list, db)
+        .await
+        .ok_or(ApiError::Database)?;
+    update_channel(subscriber, db)
+        .await
+        .ok_or(ApiError::Database)
+}
+
+pub async fn unsubscribe_ban_list(
+    subscriber: &String,
+    owner: &String,
+    name: &String,
+    db: &Surreal<Client>,
+) -> Option<Channel> {
+    try_unsubscribe_ban_list(subscriber, owner, name, db)
+        .await
+        .ok()
+}
+
+pub async fn try_unsubscribe_ban_list(
+    subscriber: &String,
+    owner: &String,
+    name: &String,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    let mut subscriber = search_channel_by_username(subscriber, db)
+        .await
+        .ok_or(ApiError::ChannelNotExists)?;
+    let (_, mut ban_list) = owned_ban_list(owner, name, db).await?;
+    let thing = ban_list_thing(&ban_list.owner.id, &ban_list.name);
+    if !subscriber.ban_list_subscriptions.contains(&thing) {
+        eprintln!("Error: Unsubscribe Ban List | Not Subscribed");
+        return Err(ApiError::NotSubscribed);
+    }
+    let subscriber_id = subscriber.id.clone().ok_or(ApiError::Database)?.id;
+    subscriber
+        .ban_list_subscriptions
+        .retain(|subscription| subscription != &thing);
+    ban_list.subscriber_list.retain(|id| id != &subscriber_id);
+    update_ban_list(ban_list, db)
+        .await
+        .ok_or(ApiError::Database)?;
+    update_channel(subscriber, db)
+        .await
+        .ok_or(ApiError::Database)
+}
+
+pub async fn ban_list(
+    owner: &String,
+    name: &String,
+    db: &Surreal<Client>,
+) -> Option<BanListResponse> {
+    let (_, ban_list) = owned_ban_list(owner, name, db).await.ok()?;
+    Some(ban_list_response(ban_list, db).await)
+}
+
+pub async fn ban_lists(owner: &String, db: &Surreal<Client>) -> Option<Vec<BanListResponse>> {
+    let owner = search_channel_by_username(owner, db).await?;
+    let mut responses = vec![];
+    for ban_list in search_ban_lists_by_owner(&owner, db).await {
+        responses.push(ban_list_response(ban_list, db).await);
+    }
+    Some(responses)
+}
+
+/// Lists `username` subscribes to, unpublished ones included.
+pub async fn ban_list_subscriptions(
+    username: &String,
+    db: &Surreal<Client>,
+) -> Option<Vec<BanListResponse>> {
+    let channel = search_channel_by_username(username, db).await?;
+    let mut responses = vec![];
+    for ban_list in search_records::<BanList>(channel.ban_list_subscriptions.clone(), db).await {
+        responses.push(ban_list_response(ban_list, db).await);
+    }
+    Some(responses)
+}
+
+pub async fn allow(allowed: &String, judge: &String, db: &Surreal<Client>) -> Option<Channel> {
+    try_allow(allowed, judge, db).await.ok()
+}
+
+/// Exempts `allowed` from the ban lists `judge` subscribes to, direct bans
+/// still apply.
+pub async fn try_allow(
+    allowed: &String,
+    judge: &String,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    let allowed = search_channel_by_username(allowed, db)
+        .await
+        .ok_or(ApiError::ChannelNotExists)?;
+    let mut judge = search_channel_by_username(judge, db)
+        .await
+        .ok_or(ApiError::ChannelNotExists)?;
+    if allowed.id == judge.id {
+        return Err(ApiError::SelfRelation);
+    }
+    let allowed_id = allowed.id.ok_or(ApiError::Database)?.id;
+    if judge.allowed_list.contains(&allowed_id) {
+        eprintln!("Error: Allow | Already Allowed");
+        return Err(ApiError::AlreadyAllowed);
+    }
+    judge.allowed_list.push(allowed_id);
+    update_channel(judge, db).await.ok_or(ApiError::Database)
+}
+
+pub async fn disallow(allowed: &String, judge: &String, db: &Surreal<Client>) -> Option<Channel> {
+    try_disallow(allowed, judge, db).await.ok()
+}
+
+pub async fn try_disallow(
+    allowed: &String,
+    judge: &String,
+    db: &Surreal<Client>,
+) -> Result<Channel, ApiError> {
+    let allowed = search_channel_by_username(allowed, db)
+        .await
+        .ok_or(ApiError::ChannelNotExists)?;
+    let mut judge = search_channel_by_username(judge, db)
+        .await
+        .ok_or(ApiError::ChannelNotExists)?;
+    let allowed_id = allowed.id.ok_or(ApiError::Database)?.id;
+    if !judge.allowed_list.contains(&allowed_id) {
+        eprintln!("Error: Disallow | Not Allowed");
+        return Err(ApiError::NotAllowed);
+    }
+    judge.allowed_list.retain(|id| id != &allowed_id);
+    update_channel(judge, db).await.ok_or(ApiError::Database)
+}
+
+pub async fn list_allowed(username: &String, db: &Surreal<Client>) -> Option<Vec<String>> {
+    let channel = search_channel_by_username(username, db).await?;
+    Some(
+        hydrate_list(&channel.allowed_list, db)
+            .await
+            .into_iter()
+            .map(|allowed| allowed.username)
+            .collect(),
+    )
+}
+
+pub async fn global_ban(
+    username: &String,
+    reason: Option<String>,
+    duration_seconds: Option<u64>,
+    db: &Surreal<Client>,
+) -> Option<GlobalBan> {
+    try_global_ban(username, reason, duration_seconds, db)
+        .await
+        .ok()
+}
+
+/// Suspends `username` on every channel, an expired global ban is replaced.
+pub async fn try_global_ban(
+    username: &String,
+    reason: Option<String>,
+    duration_seconds: Option<u64>,
+    db: &Surreal<Client>,
+) -> Result<GlobalBan, ApiError> {
+    let channel = search_channel_by_username(username, db)
+        .await
+        .ok_or(ApiError::ChannelNotExists)?;
+    if is_globally_banned_channel(&channel, db).await {
+        eprintln!("Error: Global Ban | Already Globally Banned");
+        return Err(ApiError::AlreadyGloballyBanned);
+    }
+    create_global_ban(new_global_ban(&channel, reason, duration_seconds), db)
+        .await
+        .ok_or(ApiError::Database)
+}
+
+pub async fn global_unban(username: &String, db: &Surreal<Client>) -> Option<GlobalBan> {
+    try_global_unban(username, db).await.ok()
+}
+
+pub async fn try_global_unban(
+    username: &String,
+    db: &Surreal<Client>,
+) -> Result<GlobalBan, ApiError> {
+    let channel = search_channel_by_username(username, db)
+        .await
+        .ok_or(ApiError::ChannelNotExists)?;
+    let global_ban = search_global_ban(&channel, db)
+        .await
+        .ok_or(ApiError::NotGloballyBanned)?;
+    delete_global_ban_record(&global_ban, db)
+        .await
+        .ok_or(ApiError::Database)
+}
+
+pub async fn is_globally_banned(username: &String, db: &Surreal<Client>) -> bool {
+    match search_channel_by_username(username, db).await {
+        Some(channel) => is_globally_banned_channel(&channel, db).await,
+        None => {
+            eprintln!("Error: Can't Check Is Globally Banned | Channel Not Exists");
+            false
+        }
+    }
+}
+
+pub async fn global_ban_details(username: &String, db: &Surreal<Client>) -> Option<GlobalBanEntry> {
+    let channel = search_channel_by_username(username, db).await?;
+    search_global_ban(&channel, db)
+        .await
+        .map(|global_ban| global_ban_entry(global_ban, channel))
+}
+
+pub async fn list_global_bans(db: &Surreal<Client>) -> Vec<GlobalBanEntry> {
+    global_ban_entries(db).await
+}
+
+/// Lifts every expired global ban, returns how many were lifted.
+pub async fn remove_expired_global_bans(db: &Surreal<Client>) -> usize {
+    let mut removed = 0;
+    for global_ban in search_expired_global_bans(db).await {
+        if delete_global_ban_record(&global_ban, db).await.is_some() {
+            removed += 1;
+        }
+    }
+    removed
+}
+
+pub async fn relationship(
+    username: &String,
+    other: &String,
+    db: &Surreal<Client>,
+) -> Option<Relationship> {
+    search_relationship_by_username(username, other, db)
+        .await
+        .map(|records| relationship_between(&records))
+}
+
+pub async fn list_relation(
+    username: &String,
+    relation: Relation,
+    pagination: &Pagination,
+    db: &Surreal<Client>,
+) -> Option<Page<RelationEntry>> {
+    try_list_relation(username, relation, pagination, Some(username), db)
+        .await
+        .ok()
+}
+
+/// Lists as seen by `viewer`, hidden follower and following lists are only
+/// listed to the channel itself and judges hiding their bans from victims
+/// are left out of banned from lists. `viewer` has to be verified by the
+/// caller.
+pub async fn try_list_relation(
+    username: &String,
+    relation: Relation,
+    pagination: &Pagination,
+    viewer: Option<&String>,
+    db: &Surreal<Client>,
+) -> Result<Page<RelationEntry>, ApiError> {
+    let channel = match search_channel_by_username(username, db).await {
+        Some(channel) => channel,
+        None => {
+            eprintln!("Error: List Relation | Channel Not Exists");
+            return Err(ApiError::ChannelNotExists);
+        }
+    };
+    let is_viewer = |channel: &Channel| {
+        viewer.is_some_and(|viewer| {
+            normalize_username(viewer) == normalize_username(&channel.username)
+        })
+    };
+    let hidden = match relation {
+        Relation::Follower => channel.settings.hide_follower_list,
+        Relation::Followed => channel.settings.hide_following_list,
+        _ => false,
+    };
+    if hidden && !is_viewer(&channel) {
+        eprintln!("Error: List Relation | List Hidden");
+        return Err(ApiError::ListHidden);
+    }
+    let candidates = relation_candidates(&channel, relation, db).await;
+    list_page(
+        candidates,
+        pagination,
+        relation == Relation::BannedFrom,
+        viewer,
+        db,
+    )
+    .await
+}
+
+/// Page of `candidates`, see `paginate`. Malformed cursors answer
+/// `cursor_invalid`.
+async fn list_page(
+    candidates: Vec<RelationCandidate>,
+    pagination: &Pagination,
+    hide_bans: bool,
+    viewer: Option<&String>,
+    db: &Surreal<Client>,
+) -> Result<Page<RelationEntry>, ApiError> {
+    let cursor = match &pagination.cursor {
+        Some(cursor) => match parse_relation_cursor(cursor, pagination.sort) {
+            Some(cursor) => Some(cursor),
+            None => {
+                eprintln!("Error: List Page | Cursor Malformed");
+                return Err(ApiError::Validation(ValidationError {
+                    field: "cursor",
+                    code: "cursor_invalid",
+                    message: "Cursor Malformed".to_string(),
+                }));
+            }
+        },
+        None => None,
+    };
+    let limit = pagination
+        .limit
+        .unwrap_or(DEFAULT_PAGE_LIMIT)
+        .clamp(1, MAX_PAGE_LIMIT);
+    paginate(candidates, cursor, pagination, limit, hide_bans, viewer, db)
+        .await
+        .ok_or(ApiError::Database)
+}
+
+pub async fn are_followers(
+    usernames: &[String],
+    followed: &String,
+    db: &Surreal<Client>,
+) -> Option<BTreeMap<String, bool>> {
+    is_in_relation_by_usernames(followed, Relation::Follower, usernames, db).await
+}
+
+pub async fn is_following(
+    follower: &String,
+    usernames: &[String],
+    db: &Surreal<Client>,
+) -> Option<BTreeMap<String, bool>> {
+    is_in_relation_by_usernames(follower, Relation::Followed, usernames, db).await
+}
+
+/// Effective ban scope of each of `usernames` by `judge`, `None` for
+/// channels that aren't banned, don't exist or whose ban doesn't cover `scope`.
+pub async fn are_banned(
+    usernames: &[String],
+    judge: &String,
+    scope: Option<BanScope>,
+    db: &Surreal<Client>,
+) -> Option<BTreeMap<String, Option<BanScope>>> {
+    let judge = search_channel_by_username(judge, db).await?;
+    let victims = search_channels_by_usernames(usernames, db).await;
+    let records = search_ban_records(&victims, std::slice::from_ref(&judge), db).await;
+    let victims: HashMap<String, Channel> = victims
+        .into_iter()
+        .map(|victim| (normalize_username(&victim.username), victim))
+        .collect();
+    let results = usernames
+        .iter()
+        .map(|username| {
+            let banned = victims
+                .get(&normalize_username(username))
+                .and_then(|victim| covering_scope(ban_scope_in(victim, &judge, &records), scope));
+            (username.clone(), banned)
+        })
+        .collect();
+    Some(results)
+}
+
+/// Effective ban scope of `victim` by each of `usernames`, `None` for
+/// channels that didn't ban it, don't exist or whose ban doesn't cover
+/// `scope`.
+pub async fn is_banned_from(
+    victim: &String,
+    usernames: &[String],
+    scope: Option<BanScope>,
+    db: &Surreal<Client>,
+) -> Option<BTreeMap<String, Option<BanScope>>> {
+    let victim = search_channel_by_username(victim, db).await?;
+    let judges = search_channels_by_usernames(usernames, db).await;
+    let records = search_ban_records(std::slice::from_ref(&victim), &judges, db).await;
+    let judges: HashMap<String, Channel> = judges
+        .into_iter()
+        .map(|judge| (normalize_username(&judge.username), judge))
+        .collect();
+    let results = usernames
+        .iter()
+        .map(|username| {
+            let banned = judges
+                .get(&normalize_username(username))
+                .and_then(|judge| covering_scope(ban_scope_in(&victim, judge, &records), scope));
+            (username.clone(), banned)
+        })
+        .collect();
+    Some(results)
+}
+
+/// Follows every channel of `usernames` with the checks of `try_follow`,
+/// answering `ok`, `requested` or the error code of each one.
+pub async fn batch_follow(
+    follower: &String,
+    usernames: &[String],
+    db: &Surreal<Client>,
+) -> Result<BTreeMap<String, &'static str>, ApiError> {
+    batch_relation(
+        follower,
+        Relation::Followed,
+        true,
+        usernames,
+        &BanDetails::default(),
+        db,
+    )
+    .await
+}
+
+pub async fn batch_unfollow(
+    follower: &String,
+    usernames: &[String],
+    db: &Surreal<Client>,
+) -> Result<BTreeMap<String, &'static str>, ApiError> {
+    batch_relation(
+        follower,
+        Relation::Followed,
+        false,
+        usernames,
+        &BanDetails::default(),
+        db,
+    )
+    .await
+}
+
+/// Bans every channel of `usernames` with the checks of `try_ban`, each one
+/// recorded with the same `details`.
+pub async fn batch_ban(
+    usernames: &[String],
+    judge: &String,
+    details: BanDetails,
+    db: &Surreal<Client>,
+) -> Result<BTreeMap<String, &'static str>, ApiError> {
+    check_ban_reason(&details)?;
+    batch_relation(judge, Relation::Banned, true, usernames, &details, db).await
+}
+
+pub async fn batch_unban(
+    usernames: &[String],
+    judge: &String,
+    db: &Surreal<Client>,
+) -> Result<BTreeMap<String, &'static str>, ApiError> {
+    batch_relation(
+        judge,
+        Relation::Banned,
+        false,
+        usernames,
+        &BanDetails::default(),
+        db,
+    )
+    .await
+}
+
+/// Adds or removes the follow or ban `relation` from `username` to every
+/// channel of `usernames`, checked like the single operations.
+///
+/// Each chunk of `batch_chunk_size` is written in one transaction, when it
+/// fails every username of the chunk answers `database` and nothing of it is
+/// written.
+async fn batch_relation(
+    username: &String,
+    relation: Relation,
+    add: bool,
+    usernames: &[String],
+    ban: &BanDetails,
+    db: &Surreal<Client>,
+) -> Result<BTreeMap<String, &'static str>, ApiError> {
+    let mut channel = search_channel_by_username(username, db)
+        .await
+        .ok_or(ApiError::ChannelNotExists)?;
+    let mut results = BTreeMap::new();
+    for chunk in usernames.chunks(api_config().batch_chunk_size.max(1)) {
+        let mut others: HashMap<String, Channel> = search_channels_by_usernames(chunk, db)
+            .await
+            .into_iter()
+            .map(|other| (normalize_username(&other.username), other))
+            .collect();
+        let unchanged = channel.clone();
+        let mut write = BatchWrite::default();
+        let mut staged: BTreeMap<String, &'static str> = BTreeMap::new();
+        let mut touched: Vec<String> = vec![];
+        for other_username in chunk {
+            if results.contains_key(other_username) || staged.contains_key(other_username) {
+                continue;
+            }
+            let normalized = normalize_username(other_username);
+            let status = match others.get_mut(&normalized) {
+                Some(other) => {
+                    stage_batch_item(&mut channel, relation, add, other, ban, &mut write, db).await
+                }
+                None => Err(ApiError::ChannelNotExists),
+            };
+            match status {
+                Ok(status) => {
+                    staged.insert(other_username.clone(), status);
+                    touched.push(normalized);
+                }
+                Err(err_val) => {
+                    results.insert(other_username.clone(), err_val.code());
+                }
+            }
+        }
+        if staged.is_empty() {
+            continue;
+        }
+        let mut channels: Vec<Channel> = touched
+            .iter()
+            .filter_map(|normalized| others.remove(normalized))
+            .collect();
+        channels.push(channel.clone());
+        let committed = update_channels(channels, write.records, write.deletions, db)
+            .await
+            .is_some();
+        if !committed {
+            channel = unchanged;
+        }
+        for (other_username, status) in staged {
+            let status = match committed {
+                true => status,
+                false => ApiError::Database.code(),
+            };
+            results.insert(other_username, status);
+        }
+    }
+    Ok(results)
+}
+
+/// Runs the checks of the single operation for one channel of a batch and
+/// stages it, answering `ok` or `requested`.
+async fn stage_batch_item(
+    channel: &mut Channel,
+    relation: Relation,
+    add: bool,
+    other: &mut Channel,
+    ban: &BanDetails,
+    write: &mut BatchWrite,
+    db: &Surreal<Client>,
+) -> Result<&'static str, ApiError> {
+    match (relation, add) {
+        (Relation::Followed, true) => {
+            if check_follow(channel, other, db).await? {
+                stage_follow_request(channel, other, true, write).ok_or(ApiError::Database)?;
+                return Ok("requested");
+            }
+        }
+        (Relation::Banned, true) => {
+            check_ban(channel, other, db).await?;
+            stage_follow_request(other, channel, false, write).ok_or(ApiError::Database)?;
+            if api_config().ban_removes_follow
+                && has_relation(other, Relation::Followed, channel)
+                && fully_banned_with(other, channel, ban.scope, db).await
+            {
+                stage_relation(other, Relation::Followed, channel, false, ban, write)
+                    .ok_or(ApiError::Database)?;
+            }
+        }
+        (relation, false) => check_relation(channel, relation, other, false)?,
+        _ => return Err(ApiError::Database),
+    }
+    stage_relation(channel, relation, other, add, ban, write).ok_or(ApiError::Database)?;
+    Ok("ok")
 }
@@ -1,12 +1,23 @@
-use crate::Channel;
+use crate::{
+    error::ApiError,
+    response::{
+        id_to_string, BanInfo, BanListResponse, ChannelCounts, GlobalBanEntry, ListSort, Page,
+        Pagination, RelationEntry, Relationship, SortOrder,
+    },
+    username::{normalize_username, username_skeleton},
+    Ban, BanDetails, BanList, BanScope, Channel, ChannelSettings, Follow, GlobalBan, Mute,
+    Relation, SchemaVersion,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use surrealdb::{
     engine::remote::ws::{Client, Ws},
     opt::auth::Root,
-    sql::Id,
+    sql::{to_value, Datetime, Duration, Id, Thing, Value},
     Surreal,
 };
 
-use super::db_operations::{unban, unfollow};
+use super::db_operations::{remove_member, remove_moderator, unban, unblock, unfollow, unmute};
 pub async fn establish_connection(
     address: &String,
     username: &String,
@@ -39,6 +50,334 @@ pub async fn search_channel_by_id(id: &Id, db: &Surreal<Client>) -> Option<Chann
     let searced: Option<Channel> = db.select(("channel", id.clone())).await.unwrap();
     searced
 }
+
+pub async fn search_channels_by_ids(ids: &[Id], db: &Surreal<Client>) -> Vec<Channel> {
+    if ids.is_empty() {
+        return vec![];
+    }
+    let things: Vec<Thing> = ids
+        .iter()
+        .map(|id| Thing::from(("channel", id.clone())))
+        .collect();
+    match db
+        .query("SELECT * FROM $channels")
+        .bind(("channels", things))
+        .await
+    {
+        Ok(mut response) => match response.take(0) {
+            Ok(channels) => channels,
+            Err(err_val) => {
+                eprintln!("Error: Search Channels By Ids | {}", err_val);
+                vec![]
+            }
+        },
+        Err(err_val) => {
+            eprintln!("Error: Search Channels By Ids | {}", err_val);
+            vec![]
+        }
+    }
+}
+
+pub async fn search_channels_by_usernames(
+    usernames: &[String],
+    db: &Surreal<Client>,
+) -> Vec<Channel> {
+    if usernames.is_empty() {
+        return vec![];
+    }
+    match db
+        .query("SELECT * FROM channel WHERE normalized_username IN $usernames")
+        .bind((
+            "usernames",
+            usernames
+                .iter()
+                .map(|username| normalize_username(username))
+                .collect::<Vec<String>>(),
+        ))
+        .await
+    {
+        Ok(mut response) => match response.take(0) {
+            Ok(channels) => channels,
+            Err(err_val) => {
+                eprintln!("Error: Search Channels By Usernames | {}", err_val);
+                vec![]
+            }
+        },
+        Err(err_val) => {
+            eprintln!("Error: Search Channels By Usernames | {}", err_val);
+            vec![]
+        }
+    }
+}
+
+pub async fn search_channel_counts_by_username(
+    username: &String,
+    db: &Surreal<Client>,
+) -> Option<ChannelCounts> {
+    match db
+        .query("SELECT follower_count, followed_count, banned_count, banned_from_count FROM channel WHERE normalized_username = $username")
+        .bind(("username", normalize_username(username)))
+        .await
+    {
+        Ok(mut response) => match response.take(0) {
+            Ok(counts) => counts,
+            Err(err_val) => {
+                eprintln!("Error: Search Channel Counts | {}", err_val);
+                None
+            }
+        },
+        Err(err_val) => {
+            eprintln!("Error: Search Channel Counts | {}", err_val);
+            None
+        }
+    }
+}
+
+pub async fn search_channel_pair_by_username(
+    username: &String,
+    other: &String,
+    db: &Surreal<Client>,
+) -> Option<(Channel, Channel)> {
+    match db
+        .query("SELECT * FROM channel WHERE normalized_username = $username; SELECT * FROM channel WHERE normalized_username = $other")
+        .bind(("username", normalize_username(username)))
+        .bind(("other", normalize_username(other)))
+        .await
+    {
+        Ok(mut response) => {
+            let channel: Option<Channel> = response.take(0).unwrap_or_default();
+            let other_channel: Option<Channel> = response.take(1).unwrap_or_default();
+            match (channel, other_channel) {
+                (Some(channel), Some(other_channel)) => Some((channel, other_channel)),
+                _ => {
+                    eprintln!("Error: Search Channel Pair | Channel Not Exists");
+                    None
+                }
+            }
+        }
+        Err(err_val) => {
+            eprintln!("Error: Search Channel Pair | {}", err_val);
+            None
+        }
+    }
+}
+
+/// Both channels of a relationship with the records between them.
+pub struct RelationshipRecords {
+    pub channel: Channel,
+    pub other: Channel,
+    pub follows: Vec<Follow>,
+    pub bans: BanRecords,
+    pub mutes: Vec<Mute>,
+}
+
+/// Loads both channels together with the follows, mutes and the ban records
+/// of `ban_scope_in` between them in one round trip, the records are
+/// selected by the keys of `follow_thing`, `ban_thing`, `mute_thing` and
+/// `global_ban_thing`.
+///
+/// Ids in channel lists are stored as `{ String: id }` objects, hence
+/// `$this.String` for the organizations.
+pub async fn search_relationship_by_username(
+    username: &String,
+    other: &String,
+    db: &Surreal<Client>,
+) -> Option<RelationshipRecords> {
+    match db
+        .query("LET $channel = (SELECT * FROM channel WHERE normalized_username = $username)[0]")
+        .query("LET $other_channel = (SELECT * FROM channel WHERE normalized_username = $other)[0]")
+        .query("LET $channel_id = meta::id($channel.id); LET $other_id = meta::id($other_channel.id)")
+        .query("RETURN $channel; RETURN $other_channel")
+        .query("SELECT * FROM [type::thing('follow', [$channel_id, $other_id]), type::thing('follow', [$other_id, $channel_id])]")
+        .query("SELECT * FROM [type::thing('mute', [$channel_id, $other_id]), type::thing('mute', [$other_id, $channel_id])]")
+        .query("SELECT * FROM [type::thing('global_ban', $channel_id), type::thing('global_ban', $other_id)]")
+        .query("SELECT * FROM array::union($channel.ban_list_subscriptions, $other_channel.ban_list_subscriptions)")
+        .query("SELECT * FROM (SELECT VALUE type::thing('channel', $this.String) FROM array::union($channel.organization_list, $other_channel.organization_list))")
+        .query("LET $organization_bans = array::union((SELECT VALUE type::thing('ban', [$other_id, $this.String]) FROM $channel.organization_list), (SELECT VALUE type::thing('ban', [$channel_id, $this.String]) FROM $other_channel.organization_list))")
+        .query("SELECT * FROM array::union([type::thing('ban', [$channel_id, $other_id]), type::thing('ban', [$other_id, $channel_id])], $organization_bans)")
+        .bind(("username", normalize_username(username)))
+        .bind(("other", normalize_username(other)))
+        .await
+    {
+        Ok(mut response) => {
+            let channel: Option<Channel> = response.take(4).unwrap_or_default();
+            let other_channel: Option<Channel> = response.take(5).unwrap_or_default();
+            let follows: Vec<Follow> = response.take(6).unwrap_or_default();
+            let mutes: Vec<Mute> = response.take(7).unwrap_or_default();
+            let bans = BanRecords {
+                global_bans: response.take(8).unwrap_or_default(),
+                ban_lists: response.take(9).unwrap_or_default(),
+                organizations: response.take(10).unwrap_or_default(),
+                bans: response.take(12).unwrap_or_default(),
+            };
+            match (channel, other_channel) {
+                (Some(channel), Some(other)) => Some(RelationshipRecords {
+                    channel,
+                    other,
+                    follows,
+                    bans,
+                    mutes,
+                }),
+                _ => {
+                    eprintln!("Error: Search Relationship | Channel Not Exists");
+                    None
+                }
+            }
+        }
+        Err(err_val) => {
+            eprintln!("Error: Search Relationship | {}", err_val);
+            None
+        }
+    }
+}
+
+/// Usernames sharing a normalized username, which was allowed before
+/// usernames were normalized.
+pub fn normalized_username_collisions(channels: &[Channel]) -> BTreeMap<String, Vec<String>> {
+    let mut usernames: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for channel in channels {
+        usernames
+            .entry(normalize_username(&channel.username))
+            .or_default()
+            .push(channel.username.clone());
+    }
+    usernames.retain(|_, usernames| usernames.len() > 1);
+    usernames
+}
+
+/// Fills the normalized username and skeleton of records created before
+/// they existed, then enforces uniqueness on the normalized username.
+///
+/// Refuses to run while usernames collide once normalized, they have to be
+/// renamed or deleted first.
+pub async fn backfill_normalized_usernames(db: &Surreal<Client>) -> Result<(), String> {
+    let channels: Vec<Channel> = db
+        .select("channel")
+        .await
+        .map_err(|err_val| format!("Backfill Normalized Usernames | {}", err_val))?;
+    let collisions = normalized_username_collisions(&channels);
+    if !collisions.is_empty() {
+        let collisions: Vec<String> = collisions
+            .into_iter()
+            .map(|(normalized_username, usernames)| {
+                format!("{} ({})", normalized_username, usernames.join(", "))
+            })
+            .collect();
+        return Err(format!(
+            "Backfill Normalized Usernames | Usernames Collide, Rename Or Delete Them: {}",
+            collisions.join("; ")
+        ));
+    }
+    for channel in channels {
+        if channel.normalized_username.is_empty() {
+            fill_normalized_username(&channel, db).await?;
+        }
+    }
+    match db
+        .query("DEFINE INDEX normalizedUsernameINDEX ON TABLE channel COLUMNS normalized_username UNIQUE")
+        .await
+    {
+        Ok(response) => match response.check() {
+            Ok(_) => Ok(()),
+            Err(err_val) => Err(format!("Backfill Normalized Usernames | {}", err_val)),
+        },
+        Err(err_val) => Err(format!("Backfill Normalized Usernames | {}", err_val)),
+    }
+}
+
+/// Writes only the normalized username and skeleton, `update_channel` would
+/// stamp `updated_at` with the time of the migration.
+async fn fill_normalized_username(channel: &Channel, db: &Surreal<Client>) -> Result<(), String> {
+    match db
+        .query("UPDATE $channel SET normalized_username = $normalized_username, username_skeleton = $username_skeleton")
+        .bind(("channel", channel.id.clone()))
+        .bind(("normalized_username", normalize_username(&channel.username)))
+        .bind(("username_skeleton", username_skeleton(&channel.username)))
+        .await
+    {
+        Ok(response) => match response.check() {
+            Ok(_) => Ok(()),
+            Err(err_val) => Err(format!("Backfill Normalized Usernames | {}", err_val)),
+        },
+        Err(err_val) => Err(format!("Backfill Normalized Usernames | {}", err_val)),
+    }
+}
+
+/// Stamps `updated_at` of channels that have none, once.
+///
+/// Channels created before timestamps were recorded keep no `created_at`
+/// rather than a made-up one, their `updated_at` is the time of this
+/// migration since nothing older is known. Later migrations leave
+/// `updated_at` alone.
+pub async fn backfill_timestamps(db: &Surreal<Client>) -> Option<()> {
+    match db
+        .query("UPDATE channel SET updated_at = created_at ?? time::now() WHERE updated_at = NONE")
+        .query("UPDATE channel SET metadata = {} WHERE metadata = NONE")
+        .await
+    {
+        Ok(_) => Some(()),
+        Err(err_val) => {
+            eprintln!("Error: Backfill Timestamps | {}", err_val);
+            None
+        }
+    }
+}
+
+/// Schema version of the database, 0 before any migration ran.
+pub async fn search_schema_version(db: &Surreal<Client>) -> Option<u32> {
+    match db.select(("schema_version", "current")).await {
+        Ok(schema_version) => Some(
+            schema_version
+                .map(|schema_version: SchemaVersion| schema_version.version)
+                .unwrap_or_default(),
+        ),
+        Err(err_val) => {
+            eprintln!("Error: Search Schema Version | {}", err_val);
+            None
+        }
+    }
+}
+
+pub async fn update_schema_version(version: u32, db: &Surreal<Client>) -> Option<SchemaVersion> {
+    match db
+        .update(("schema_version", "current"))
+        .content(SchemaVersion { version })
+        .await
+    {
+        Ok(schema_version) => schema_version,
+        Err(err_val) => {
+            eprintln!("Error: Update Schema Version | {}", err_val);
+            None
+        }
+    }
+}
+
+pub async fn define_username_index(db: &Surreal<Client>) -> Option<()> {
+    match db
+        .query("DEFINE INDEX usernameINDEX ON TABLE channel COLUMNS username UNIQUE")
+        .await
+    {
+        Ok(_) => Some(()),
+        Err(err_val) => {
+            eprintln!("Error: Define Username Index | {}", err_val);
+            None
+        }
+    }
+}
+
+pub async fn backfill_counts(db: &Surreal<Client>) -> Option<()> {
+    match db
+        .query("UPDATE channel SET follower_count = array::len(follower_list), followed_count = array::len(followed_list), banned_count = array::len(banned_list), banned_from_count = array::len(banned_from_list)")
+        .await
+    {
+        Ok(_) => Some(()),
+        Err(err_val) => {
+            eprintln!("Error: Backfill Counts | {}", err_val);
+            None
+        }
+    }
+}
+
 fn id_extractor(channel: &Channel) -> Id {
     match channel.id.clone() {
         Some(thing) => thing.id,
@@ -53,20 +392,96 @@ pub async fn search_channel_by_username(
     username: &String,
     db: &Surreal<Client>,
 ) -> Option<crate::Channel> {
-    let searched: Vec<Option<Channel>> = db.select("channel").await.unwrap();
-    for element in searched {
-        match element {
-            Some(channel) => {
-                if channel.username == *username {
-                    return Some(channel);
+    match db
+        .query("SELECT * FROM channel WHERE normalized_username = $username")
+        .bind(("username", normalize_username(username)))
+        .await
+    {
+        Ok(mut response) => match response.take::<Vec<Channel>>(0) {
+            Ok(mut channels) => {
+                if channels.len() > 1 {
+                    eprintln!(
+                        "Error: Search Channel By Username | Normalized Username Collides: {}",
+                        username
+                    );
+                }
+                match channels
+                    .iter()
+                    .position(|channel| channel.username == *username)
+                {
+                    Some(position) => Some(channels.swap_remove(position)),
+                    None => channels.pop(),
                 }
             }
-            None => {
-                eprintln!("No Content");
+            Err(err_val) => {
+                eprintln!("Error: Search Channel By Username | {}", err_val);
+                None
             }
+        },
+        Err(err_val) => {
+            eprintln!("Error: Search Channel By Username | {}", err_val);
+            None
+        }
+    }
+}
+
+/// Channels which used `username` before, most recent change first.
+pub async fn search_channels_by_previous_username(
+    username: &String,
+    db: &Surreal<Client>,
+) -> Vec<Channel> {
+    let normalized_username = normalize_username(username);
+    let mut channels: Vec<Channel> = match db
+        .query(
+            "SELECT * FROM channel WHERE username_history.normalized_username CONTAINS $username",
+        )
+        .bind(("username", &normalized_username))
+        .await
+    {
+        Ok(mut response) => match response.take(0) {
+            Ok(channels) => channels,
+            Err(err_val) => {
+                eprintln!("Error: Search Channels By Previous Username | {}", err_val);
+                vec![]
+            }
+        },
+        Err(err_val) => {
+            eprintln!("Error: Search Channels By Previous Username | {}", err_val);
+            vec![]
+        }
+    };
+    channels.sort_by_key(|channel| std::cmp::Reverse(last_used(channel, &normalized_username)));
+    channels
+}
+
+/// When `channel` stopped using the normalized username.
+pub fn last_used(channel: &Channel, normalized_username: &String) -> Option<Datetime> {
+    channel
+        .username_history
+        .iter()
+        .filter(|change| change.normalized_username == *normalized_username)
+        .map(|change| change.changed_at.clone())
+        .max()
+}
+
+pub async fn search_channels_by_skeleton(skeleton: &String, db: &Surreal<Client>) -> Vec<Channel> {
+    match db
+        .query("SELECT * FROM channel WHERE username_skeleton = $skeleton")
+        .bind(("skeleton", skeleton))
+        .await
+    {
+        Ok(mut response) => match response.take(0) {
+            Ok(channels) => channels,
+            Err(err_val) => {
+                eprintln!("Error: Search Channels By Skeleton | {}", err_val);
+                vec![]
+            }
+        },
+        Err(err_val) => {
+            eprintln!("Error: Search Channels By Skeleton | {}", err_val);
+            vec![]
         }
     }
-    None
 }
 
 pub async fn create_channel(username: &String, db: &Surreal<Client>) -> Vec<Option<Channel>> {
@@ -76,18 +491,37 @@ pub async fn create_channel(username: &String, db: &Surreal<Client>) -> Vec<Opti
             vec![]
         }
         None => {
-            db.query("DEFINE INDEX usernameINDEX ON TABLE channel COLUMNS username UNIQUE")
-                .await
-                .unwrap();
             let created: Vec<Option<Channel>> = db
                 .create("channel")
                 .content(Channel {
                     id: None,
                     username: username.to_string(),
+                    normalized_username: normalize_username(username),
+                    username_skeleton: username_skeleton(username),
+                    username_history: vec![],
+                    display_name: None,
+                    metadata: BTreeMap::new(),
+                    created_at: Some(Datetime::default()),
+                    updated_at: Some(Datetime::default()),
                     follower_list: vec![],
                     banned_list: vec![],
                     followed_list: vec![],
                     banned_from_list: vec![],
+                    muted_list: vec![],
+                    muted_from_list: vec![],
+                    blocked_list: vec![],
+                    blocked_from_list: vec![],
+                    moderator_list: vec![],
+                    moderating_list: vec![],
+                    member_list: vec![],
+                    organization_list: vec![],
+                    ban_list_subscriptions: vec![],
+                    allowed_list: vec![],
+                    settings: ChannelSettings::default(),
+                    follower_count: 0,
+                    banned_count: 0,
+                    followed_count: 0,
+                    banned_from_count: 0,
                 })
                 .await
                 .unwrap();
@@ -109,17 +543,45 @@ pub async fn delete_channel(username: &String, db: &Surreal<Client>) -> Option<C
     }
 }
 
+/// Recomputes the fields derived from the lists and the username and
+/// stamps `updated_at`.
+fn with_derived_fields(channel: Channel) -> Channel {
+    Channel {
+        id: channel.id,
+        normalized_username: normalize_username(&channel.username),
+        username_skeleton: username_skeleton(&channel.username),
+        username: channel.username,
+        username_history: channel.username_history,
+        display_name: channel.display_name,
+        metadata: channel.metadata,
+        created_at: channel.created_at,
+        updated_at: Some(Datetime::default()),
+        follower_count: channel.follower_list.len(),
+        banned_count: channel.banned_list.len(),
+        followed_count: channel.followed_list.len(),
+        banned_from_count: channel.banned_from_list.len(),
+        follower_list: channel.follower_list,
+        banned_list: channel.banned_list,
+        followed_list: channel.followed_list,
+        banned_from_list: channel.banned_from_list,
+        muted_list: channel.muted_list,
+        muted_from_list: channel.muted_from_list,
+        blocked_list: channel.blocked_list,
+        blocked_from_list: channel.blocked_from_list,
+        moderator_list: channel.moderator_list,
+        moderating_list: channel.moderating_list,
+        member_list: channel.member_list,
+        organization_list: channel.organization_list,
+        ban_list_subscriptions: channel.ban_list_subscriptions,
+        allowed_list: channel.allowed_list,
+        settings: channel.settings,
+    }
+}
+
 pub async fn update_channel(channel: Channel, db: &Surreal<Client>) -> Option<Channel> {
     match db
         .update(("channel", channel.clone().id.unwrap()))
-        .content(Channel {
-            id: channel.id,
-            username: channel.username,
-            follower_list: channel.follower_list,
-            banned_list: channel.banned_list,
-            followed_list: channel.followed_list,
-            banned_from_list: channel.banned_from_list,
-        })
+        .content(with_derived_fields(channel))
         .await
     {
         Ok(option_channel) => match option_channel {
@@ -130,37 +592,791 @@ pub async fn update_channel(channel: Channel, db: &Surreal<Client>) -> Option<Ch
             }
         },
         Err(err_val) => {
-            eprintln!("Update Failed: {}", err_val);
-            None
+            eprintln!("Update Failed: {}", err_val);
+            None
+        }
+    }
+}
+
+/// Writes every channel and relation record, and deletes the `deletions`
+/// records, in a single transaction.
+pub async fn update_channels(
+    channels: Vec<Channel>,
+    records: Vec<(Thing, Value)>,
+    deletions: Vec<Thing>,
+    db: &Surreal<Client>,
+) -> Option<()> {
+    let mut query = db.query("BEGIN TRANSACTION");
+    for (index, channel) in channels.into_iter().enumerate() {
+        query = query
+            .query(format!("UPDATE $channel{0} CONTENT $content{0}", index))
+            .bind((format!("channel{}", index), channel.id.clone()))
+            .bind((format!("content{}", index), with_derived_fields(channel)));
+    }
+    for (index, (thing, record)) in records.into_iter().enumerate() {
+        query = query
+            .query(format!(
+                "UPDATE $record{0} CONTENT $record_content{0}",
+                index
+            ))
+            .bind((format!("record{}", index), thing))
+            .bind((format!("record_content{}", index), record));
+    }
+    for (index, thing) in deletions.into_iter().enumerate() {
+        query = query
+            .query(format!("DELETE $deletion{}", index))
+            .bind((format!("deletion{}", index), thing));
+    }
+    match query.query("COMMIT TRANSACTION").await {
+        Ok(response) => match response.check() {
+            Ok(_) => Some(()),
+            Err(err_val) => {
+                eprintln!("Error: Update Channels | {}", err_val);
+                None
+            }
+        },
+        Err(err_val) => {
+            eprintln!("Error: Update Channels | {}", err_val);
+            None
+        }
+    }
+}
+
+/// Relation records a batch chunk writes or deletes with its channels.
+#[derive(Debug, Default)]
+pub struct BatchWrite {
+    pub records: Vec<(Thing, Value)>,
+    pub deletions: Vec<Thing>,
+}
+
+/// Adds or removes the follow or ban `relation` from `channel` to `other` on
+/// the lists of both, and stages its record in `write`.
+pub fn stage_relation(
+    channel: &mut Channel,
+    relation: Relation,
+    other: &mut Channel,
+    add: bool,
+    ban: &BanDetails,
+    write: &mut BatchWrite,
+) -> Option<()> {
+    let (channel_id, other_id) = (id_extractor(channel), id_extractor(other));
+    let (thing, record) = match relation {
+        Relation::Followed => (
+            follow_thing(&channel_id, &other_id),
+            to_value(new_follow(channel, other, None)),
+        ),
+        Relation::Banned => (
+            ban_thing(&other_id, &channel_id),
+            to_value(new_ban(other, channel, ban)),
+        ),
+        _ => {
+            eprintln!("Error: Stage Relation | Not Batched");
+            return None;
+        }
+    };
+    match add {
+        true => match record {
+            Ok(record) => write.records.push((thing, record)),
+            Err(err_val) => {
+                eprintln!("Error: Stage Relation | {}", err_val);
+                return None;
+            }
+        },
+        false => write.deletions.push(thing),
+    }
+    let channel_list = relation.list_mut(channel);
+    match add {
+        true if !channel_list.contains(&other_id) => channel_list.push(other_id),
+        true => {}
+        false => channel_list.retain(|id| *id != other_id),
+    }
+    let other_list = relation.inverse().list_mut(other);
+    match add {
+        true if !other_list.contains(&channel_id) => other_list.push(channel_id),
+        true => {}
+        false => other_list.retain(|id| *id != channel_id),
+    }
+    Some(())
+}
+
+/// Stages the creation or deletion of the follow request of `follower`.
+pub fn stage_follow_request(
+    follower: &Channel,
+    followed: &Channel,
+    add: bool,
+    write: &mut BatchWrite,
+) -> Option<()> {
+    let thing = follow_request_thing(&id_extractor(follower), &id_extractor(followed));
+    match add {
+        true => match to_value(new_follow(follower, followed, None)) {
+            Ok(request) => write.records.push((thing, request)),
+            Err(err_val) => {
+                eprintln!("Error: Stage Follow Request | {}", err_val);
+                return None;
+            }
+        },
+        false => write.deletions.push(thing),
+    }
+    Some(())
+}
+
+/// Follow records are keyed by the follower and followed ids.
+pub fn follow_thing(follower: &Id, followed: &Id) -> Thing {
+    Thing::from((
+        "follow",
+        Id::from(vec![id_to_string(follower), id_to_string(followed)]),
+    ))
+}
+
+pub fn new_follow(follower: &Channel, followed: &Channel, source: Option<String>) -> Follow {
+    Follow {
+        id: None,
+        follower: Thing::from(("channel", id_extractor(follower))),
+        followed: Thing::from(("channel", id_extractor(followed))),
+        source,
+        created_at: Datetime::default(),
+    }
+}
+
+pub async fn create_follow(follow: Follow, db: &Surreal<Client>) -> Option<Follow> {
+    let thing = follow_thing(&follow.follower.id, &follow.followed.id);
+    match db.update(("follow", thing.id)).content(follow).await {
+        Ok(follow) => follow,
+        Err(err_val) => {
+            eprintln!("Error: Create Follow | {}", err_val);
+            None
+        }
+    }
+}
+
+pub async fn delete_follow(
+    follower: &Channel,
+    followed: &Channel,
+    db: &Surreal<Client>,
+) -> Option<Follow> {
+    let thing = follow_thing(&id_extractor(follower), &id_extractor(followed));
+    match db.delete(("follow", thing.id)).await {
+        Ok(follow) => follow,
+        Err(err_val) => {
+            eprintln!("Error: Delete Follow | {}", err_val);
+            None
+        }
+    }
+}
+
+/// Pending follows of channels accepting follows on approval, stored as `Follow` records in the
+/// `follow_request` table under the same pair of ids.
+pub fn follow_request_thing(follower: &Id, followed: &Id) -> Thing {
+    Thing::from((
+        "follow_request",
+        Id::from(vec![id_to_string(follower), id_to_string(followed)]),
+    ))
+}
+
+pub async fn create_follow_request(request: Follow, db: &Surreal<Client>) -> Option<Follow> {
+    let thing = follow_request_thing(&request.follower.id, &request.followed.id);
+    match db
+        .update(("follow_request", thing.id))
+        .content(request)
+        .await
+    {
+        Ok(request) => request,
+        Err(err_val) => {
+            eprintln!("Error: Create Follow Request | {}", err_val);
+            None
+        }
+    }
+}
+
+pub async fn search_follow_request(
+    follower: &Channel,
+    followed: &Channel,
+    db: &Surreal<Client>,
+) -> Option<Follow> {
+    let thing = follow_request_thing(&id_extractor(follower), &id_extractor(followed));
+    match db.select(("follow_request", thing.id)).await {
+        Ok(request) => request,
+        Err(err_val) => {
+            eprintln!("Error: Search Follow Request | {}", err_val);
+            None
+        }
+    }
+}
+
+pub async fn delete_follow_request(
+    follower: &Channel,
+    followed: &Channel,
+    db: &Surreal<Client>,
+) -> Option<Follow> {
+    let thing = follow_request_thing(&id_extractor(follower), &id_extractor(followed));
+    match db.delete(("follow_request", thing.id)).await {
+        Ok(request) => request,
+        Err(err_val) => {
+            eprintln!("Error: Delete Follow Request | {}", err_val);
+            None
+        }
+    }
+}
+
+/// Removes every request made by or waiting for `channel`.
+pub async fn delete_all_follow_requests(channel: &Channel, db: &Surreal<Client>) -> Option<()> {
+    match db
+        .query("DELETE follow_request WHERE follower = $channel OR followed = $channel")
+        .bind(("channel", Thing::from(("channel", id_extractor(channel)))))
+        .await
+    {
+        Ok(_) => Some(()),
+        Err(err_val) => {
+            eprintln!("Error: Delete All Follow Requests | {}", err_val);
+            None
+        }
+    }
+}
+
+/// Ban records are keyed by the victim and judge ids.
+pub fn ban_thing(victim: &Id, judge: &Id) -> Thing {
+    Thing::from((
+        "ban",
+        Id::from(vec![id_to_string(victim), id_to_string(judge)]),
+    ))
+}
+
+pub fn new_ban(victim: &Channel, judge: &Channel, details: &BanDetails) -> Ban {
+    Ban {
+        id: None,
+        victim: Thing::from(("channel", id_extractor(victim))),
+        judge: Thing::from(("channel", id_extractor(judge))),
+        reason: details.reason.clone(),
+        moderator: details.moderator.clone(),
+        notes: details.notes.clone(),
+        created_at: Datetime::default(),
+        expires_at: details
+            .duration_seconds
+            .map(|duration_seconds| Duration::from_secs(duration_seconds) + Datetime::default()),
+        scope: details.scope,
+    }
+}
+
+pub async fn search_ban(victim: &Channel, judge: &Channel, db: &Surreal<Client>) -> Option<Ban> {
+    search_records(
+        vec![ban_thing(&id_extractor(victim), &id_extractor(judge))],
+        db,
+    )
+    .await
+    .pop()
+}
+
+pub async fn search_expired_bans(db: &Surreal<Client>) -> Vec<Ban> {
+    match db
+        .query("SELECT * FROM ban WHERE expires_at != NONE AND expires_at <= time::now()")
+        .await
+    {
+        Ok(mut response) => match response.take(0) {
+            Ok(bans) => bans,
+            Err(err_val) => {
+                eprintln!("Error: Search Expired Bans | {}", err_val);
+                vec![]
+            }
+        },
+        Err(err_val) => {
+            eprintln!("Error: Search Expired Bans | {}", err_val);
+            vec![]
+        }
+    }
+}
+
+pub async fn delete_ban_record(ban: &Ban, db: &Surreal<Client>) -> Option<Ban> {
+    let thing = ban_thing(&ban.victim.id, &ban.judge.id);
+    match db.delete(("ban", thing.id)).await {
+        Ok(ban) => ban,
+        Err(err_val) => {
+            eprintln!("Error: Delete Ban | {}", err_val);
+            None
+        }
+    }
+}
+
+/// Global bans are keyed by the banned channel's id.
+pub fn global_ban_thing(channel: &Id) -> Thing {
+    Thing::from(("global_ban", channel.clone()))
+}
+
+pub fn new_global_ban(
+    channel: &Channel,
+    reason: Option<String>,
+    duration_seconds: Option<u64>,
+) -> GlobalBan {
+    GlobalBan {
+        id: None,
+        channel: Thing::from(("channel", id_extractor(channel))),
+        reason,
+        created_at: Datetime::default(),
+        expires_at: duration_seconds
+            .map(|duration_seconds| Duration::from_secs(duration_seconds) + Datetime::default()),
+    }
+}
+
+pub async fn create_global_ban(global_ban: GlobalBan, db: &Surreal<Client>) -> Option<GlobalBan> {
+    let thing = global_ban_thing(&global_ban.channel.id);
+    match db
+        .update(("global_ban", thing.id))
+        .content(global_ban)
+        .await
+    {
+        Ok(global_ban) => global_ban,
+        Err(err_val) => {
+            eprintln!("Error: Create Global Ban | {}", err_val);
+            None
+        }
+    }
+}
+
+pub async fn search_global_ban(channel: &Channel, db: &Surreal<Client>) -> Option<GlobalBan> {
+    search_records(vec![global_ban_thing(&id_extractor(channel))], db)
+        .await
+        .pop()
+}
+
+pub async fn search_global_bans(db: &Surreal<Client>) -> Vec<GlobalBan> {
+    match db.query("SELECT * FROM global_ban").await {
+        Ok(mut response) => match response.take(0) {
+            Ok(global_bans) => global_bans,
+            Err(err_val) => {
+                eprintln!("Error: Search Global Bans | {}", err_val);
+                vec![]
+            }
+        },
+        Err(err_val) => {
+            eprintln!("Error: Search Global Bans | {}", err_val);
+            vec![]
+        }
+    }
+}
+
+pub async fn search_expired_global_bans(db: &Surreal<Client>) -> Vec<GlobalBan> {
+    match db
+        .query("SELECT * FROM global_ban WHERE expires_at != NONE AND expires_at <= time::now()")
+        .await
+    {
+        Ok(mut response) => match response.take(0) {
+            Ok(global_bans) => global_bans,
+            Err(err_val) => {
+                eprintln!("Error: Search Expired Global Bans | {}", err_val);
+                vec![]
+            }
+        },
+        Err(err_val) => {
+            eprintln!("Error: Search Expired Global Bans | {}", err_val);
+            vec![]
+        }
+    }
+}
+
+pub async fn delete_global_ban_record(
+    global_ban: &GlobalBan,
+    db: &Surreal<Client>,
+) -> Option<GlobalBan> {
+    let thing = global_ban_thing(&global_ban.channel.id);
+    match db.delete(("global_ban", thing.id)).await {
+        Ok(global_ban) => global_ban,
+        Err(err_val) => {
+            eprintln!("Error: Delete Global Ban | {}", err_val);
+            None
+        }
+    }
+}
+
+/// Every global ban with the banned channel's username.
+pub async fn global_ban_entries(db: &Surreal<Client>) -> Vec<GlobalBanEntry> {
+    let global_bans = search_global_bans(db).await;
+    let ids: Vec<Id> = global_bans
+        .iter()
+        .map(|global_ban| global_ban.channel.id.clone())
+        .collect();
+    let mut channels: HashMap<String, Channel> = search_channels_by_ids(&ids, db)
+        .await
+        .into_iter()
+        .map(|channel| (id_to_string(&id_extractor(&channel)), channel))
+        .collect();
+    global_bans
+        .into_iter()
+        .filter_map(|global_ban| {
+            let channel = channels.remove(&id_to_string(&global_ban.channel.id))?;
+            Some(global_ban_entry(global_ban, channel))
+        })
+        .collect()
+}
+
+pub fn global_ban_entry(global_ban: GlobalBan, channel: Channel) -> GlobalBanEntry {
+    GlobalBanEntry {
+        id: id_to_string(&id_extractor(&channel)),
+        username: channel.username,
+        reason: global_ban.reason,
+        since: global_ban.created_at.to_raw(),
+        expires_at: global_ban.expires_at.map(|expires_at| expires_at.to_raw()),
+    }
+}
+
+/// Ban lists are keyed by the owner id and the lowercased name.
+pub fn ban_list_thing(owner: &Id, name: &str) -> Thing {
+    Thing::from((
+        "ban_list",
+        Id::from(vec![id_to_string(owner), name.to_lowercase()]),
+    ))
+}
+
+pub fn new_ban_list(owner: &Channel, name: &str) -> BanList {
+    BanList {
+        id: None,
+        owner: Thing::from(("channel", id_extractor(owner))),
+        name: name.to_string(),
+        published: false,
+        entry_list: vec![],
+        subscriber_list: vec![],
+        created_at: Datetime::default(),
+    }
+}
+
+pub async fn search_ban_list(owner: &Channel, name: &str, db: &Surreal<Client>) -> Option<BanList> {
+    search_records(vec![ban_list_thing(&id_extractor(owner), name)], db)
+        .await
+        .pop()
+}
+
+pub async fn search_ban_lists_by_owner(owner: &Channel, db: &Surreal<Client>) -> Vec<BanList> {
+    match db
+        .query("SELECT * FROM ban_list WHERE owner = $owner")
+        .bind(("owner", Thing::from(("channel", id_extractor(owner)))))
+        .await
+    {
+        Ok(mut response) => match response.take(0) {
+            Ok(ban_lists) => ban_lists,
+            Err(err_val) => {
+                eprintln!("Error: Search Ban Lists | {}", err_val);
+                vec![]
+            }
+        },
+        Err(err_val) => {
+            eprintln!("Error: Search Ban Lists | {}", err_val);
+            vec![]
+        }
+    }
+}
+
+/// Creates or replaces `ban_list`.
+pub async fn update_ban_list(ban_list: BanList, db: &Surreal<Client>) -> Option<BanList> {
+    let thing = ban_list_thing(&ban_list.owner.id, &ban_list.name);
+    match db.update(("ban_list", thing.id)).content(ban_list).await {
+        Ok(ban_list) => ban_list,
+        Err(err_val) => {
+            eprintln!("Error: Update Ban List | {}", err_val);
+            None
+        }
+    }
+}
+
+pub async fn delete_ban_list_record(ban_list: &BanList, db: &Surreal<Client>) -> Option<BanList> {
+    let thing = ban_list_thing(&ban_list.owner.id, &ban_list.name);
+    match db.delete(("ban_list", thing.id)).await {
+        Ok(ban_list) => ban_list,
+        Err(err_val) => {
+            eprintln!("Error: Delete Ban List | {}", err_val);
+            None
+        }
+    }
+}
+
+/// Drops `channel` from the entries and subscribers of every ban list.
+pub async fn remove_from_all_ban_lists(channel: &Channel, db: &Surreal<Client>) -> Option<()> {
+    match db
+        .query("UPDATE ban_list SET entry_list -= $id, subscriber_list -= $id")
+        .bind(("id", id_extractor(channel)))
+        .await
+    {
+        Ok(_) => Some(()),
+        Err(err_val) => {
+            eprintln!("Error: Remove From All Ban Lists | {}", err_val);
+            None
+        }
+    }
+}
+
+pub async fn ban_list_response(ban_list: BanList, db: &Surreal<Client>) -> BanListResponse {
+    let owner = search_channel_by_id(&ban_list.owner.id, db)
+        .await
+        .map(|owner| owner.username)
+        .unwrap_or_default();
+    let entries = hydrate_list(&ban_list.entry_list, db)
+        .await
+        .into_iter()
+        .map(|entry| entry.username)
+        .collect();
+    BanListResponse {
+        owner,
+        name: ban_list.name,
+        published: ban_list.published,
+        entries,
+        subscriber_count: ban_list.subscriber_list.len(),
+        created_at: ban_list.created_at.to_raw(),
+    }
+}
+
+/// Ban lists `judge` subscribes to that are still published or owned by
+/// `judge`.
+pub async fn subscribed_ban_lists(judge: &Channel, db: &Surreal<Client>) -> Vec<BanList> {
+    let judge_id = id_extractor(judge);
+    search_records::<BanList>(judge.ban_list_subscriptions.clone(), db)
+        .await
+        .into_iter()
+        .filter(|ban_list| ban_list.published || ban_list.owner.id == judge_id)
+        .collect()
+}
+
+/// `victim` is in a ban list `judge` subscribes to and `judge` hasn't allowed
+/// it.
+pub async fn is_banned_by_ban_lists(
+    victim: &Channel,
+    judge: &Channel,
+    db: &Surreal<Client>,
+) -> bool {
+    let victim_id = id_extractor(victim);
+    !judge.allowed_list.contains(&victim_id)
+        && subscribed_ban_lists(judge, db)
+            .await
+            .iter()
+            .any(|ban_list| ban_list.entry_list.contains(&victim_id))
+}
+
+/// `channel` is under a global ban that hasn't expired.
+pub async fn is_globally_banned_channel(channel: &Channel, db: &Surreal<Client>) -> bool {
+    search_global_ban(channel, db)
+        .await
+        .is_some_and(|global_ban| !global_ban.is_expired())
+}
+
+pub async fn create_ban(ban: Ban, db: &Surreal<Client>) -> Option<Ban> {
+    let thing = ban_thing(&ban.victim.id, &ban.judge.id);
+    match db.update(("ban", thing.id)).content(ban).await {
+        Ok(ban) => ban,
+        Err(err_val) => {
+            eprintln!("Error: Create Ban | {}", err_val);
+            None
+        }
+    }
+}
+
+pub async fn delete_ban(victim: &Channel, judge: &Channel, db: &Surreal<Client>) -> Option<Ban> {
+    let thing = ban_thing(&id_extractor(victim), &id_extractor(judge));
+    match db.delete(("ban", thing.id)).await {
+        Ok(ban) => ban,
+        Err(err_val) => {
+            eprintln!("Error: Delete Ban | {}", err_val);
+            None
+        }
+    }
+}
+
+/// Mute records are keyed by the victim and judge ids.
+pub fn mute_thing(victim: &Id, judge: &Id) -> Thing {
+    Thing::from((
+        "mute",
+        Id::from(vec![id_to_string(victim), id_to_string(judge)]),
+    ))
+}
+
+pub fn new_mute(
+    victim: &Channel,
+    judge: &Channel,
+    duration_seconds: Option<u64>,
+    moderator: Option<String>,
+) -> Mute {
+    Mute {
+        id: None,
+        victim: Thing::from(("channel", id_extractor(victim))),
+        judge: Thing::from(("channel", id_extractor(judge))),
+        moderator,
+        created_at: Datetime::default(),
+        expires_at: duration_seconds
+            .map(|duration_seconds| Duration::from_secs(duration_seconds) + Datetime::default()),
+    }
+}
+
+pub async fn create_mute(mute: Mute, db: &Surreal<Client>) -> Option<Mute> {
+    let thing = mute_thing(&mute.victim.id, &mute.judge.id);
+    match db.update(("mute", thing.id)).content(mute).await {
+        Ok(mute) => mute,
+        Err(err_val) => {
+            eprintln!("Error: Create Mute | {}", err_val);
+            None
+        }
+    }
+}
+
+pub async fn search_mute(victim: &Channel, judge: &Channel, db: &Surreal<Client>) -> Option<Mute> {
+    search_records(
+        vec![mute_thing(&id_extractor(victim), &id_extractor(judge))],
+        db,
+    )
+    .await
+    .pop()
+}
+
+pub async fn search_expired_mutes(db: &Surreal<Client>) -> Vec<Mute> {
+    match db
+        .query("SELECT * FROM mute WHERE expires_at != NONE AND expires_at <= time::now()")
+        .await
+    {
+        Ok(mut response) => match response.take(0) {
+            Ok(mutes) => mutes,
+            Err(err_val) => {
+                eprintln!("Error: Search Expired Mutes | {}", err_val);
+                vec![]
+            }
+        },
+        Err(err_val) => {
+            eprintln!("Error: Search Expired Mutes | {}", err_val);
+            vec![]
+        }
+    }
+}
+
+pub async fn delete_mute(victim: &Channel, judge: &Channel, db: &Surreal<Client>) -> Option<Mute> {
+    let thing = mute_thing(&id_extractor(victim), &id_extractor(judge));
+    match db.delete(("mute", thing.id)).await {
+        Ok(mute) => mute,
+        Err(err_val) => {
+            eprintln!("Error: Delete Mute | {}", err_val);
+            None
+        }
+    }
+}
+
+pub async fn delete_mute_record(mute: &Mute, db: &Surreal<Client>) -> Option<Mute> {
+    let thing = mute_thing(&mute.victim.id, &mute.judge.id);
+    match db.delete(("mute", thing.id)).await {
+        Ok(mute) => mute,
+        Err(err_val) => {
+            eprintln!("Error: Delete Mute | {}", err_val);
+            None
+        }
+    }
+}
+
+/// Loads the existing records among `things`, missing ones are skipped.
+pub async fn search_records<T: DeserializeOwned>(
+    things: Vec<Thing>,
+    db: &Surreal<Client>,
+) -> Vec<T> {
+    if things.is_empty() {
+        return vec![];
+    }
+    match db
+        .query("SELECT * FROM $records")
+        .bind(("records", things))
+        .await
+    {
+        Ok(mut response) => match response.take(0) {
+            Ok(records) => records,
+            Err(err_val) => {
+                eprintln!("Error: Search Records | {}", err_val);
+                vec![]
+            }
+        },
+        Err(err_val) => {
+            eprintln!("Error: Search Records | {}", err_val);
+            vec![]
         }
     }
 }
+
 fn add_id_to_vector(id: Id, mut data: Vec<Id>) -> Option<Vec<Id>> {
-    data.sort();
-    match data.binary_search(&id) {
-        Ok(_) => {
+    match data.contains(&id) {
+        true => {
             eprintln!("Error: Already Contains");
             None
         }
-        Err(_) => {
+        false => {
             data.push(id);
             Some(data)
         }
     }
 }
 fn remove_id_from_vector(id: Id, mut data: Vec<Id>) -> Option<Vec<Id>> {
-    data.sort();
-    match data.binary_search(&id) {
-        Ok(_) => {
+    match data.contains(&id) {
+        true => {
             data.retain(|_id| *_id != id);
             Some(data)
         }
-        Err(_) => {
+        false => {
             eprintln!("Error: Not Contains");
             None
         }
     }
 }
+/// Adds `other` to the `relation` list of `username`.
+pub async fn add_relation(
+    other: &String,
+    username: &String,
+    relation: Relation,
+    db: &Surreal<Client>,
+) -> Option<Channel> {
+    match search_channel_by_username(username, db).await {
+        Some(mut channel) => match search_channel_by_username(other, db).await {
+            Some(other) => {
+                match add_id_to_vector(id_extractor(&other), relation.list(&channel).clone()) {
+                    Some(list) => {
+                        *relation.list_mut(&mut channel) = list;
+                        update_channel(channel, db).await
+                    }
+                    None => {
+                        eprintln!("Error: Add Relation Id");
+                        None
+                    }
+                }
+            }
+            None => {
+                eprintln!("Error: Other Not Exists");
+                None
+            }
+        },
+        None => {
+            eprintln!("Error: Add Relation");
+            None
+        }
+    }
+}
+/// Removes `other` from the `relation` list of `username`.
+pub async fn remove_relation(
+    other: &String,
+    username: &String,
+    relation: Relation,
+    db: &Surreal<Client>,
+) -> Option<Channel> {
+    match search_channel_by_username(username, db).await {
+        Some(mut channel) => match search_channel_by_username(other, db).await {
+            Some(other) => {
+                match remove_id_from_vector(id_extractor(&other), relation.list(&channel).clone()) {
+                    Some(list) => {
+                        *relation.list_mut(&mut channel) = list;
+                        update_channel(channel, db).await
+                    }
+                    None => {
+                        eprintln!("Error: Remove Relation Id");
+                        None
+                    }
+                }
+            }
+            None => {
+                eprintln!("Error: Other Not Exists");
+                None
+            }
+        },
+        None => {
+            eprintln!("Error: Remove Relation");
+            None
+        }
+    }
+}
 pub async fn add_follower(
     follower: &String,
     username: &String,
@@ -476,6 +1692,150 @@ pub async fn remove_all_banned_from(channel: Channel, db: &Surreal<Client>) -> O
     search_channel_by_username(&channel.username, db).await
 }
 
+pub async fn remove_all_muted(channel: Channel, db: &Surreal<Client>) -> Option<Channel> {
+    for id in channel.muted_list {
+        match search_channel_by_id(&id, db).await {
+            Some(muted_channel) => {
+                match unmute(&muted_channel.username, &channel.username, db).await {
+                    Some(_) => {}
+                    None => {
+                        eprintln!("Error: Can't Remove Muted");
+                    }
+                }
+            }
+            None => {
+                eprintln!("Error: Can't Remove Muted, Muted Not Exists");
+            }
+        }
+    }
+    search_channel_by_username(&channel.username, db).await
+}
+pub async fn remove_all_muted_from(channel: Channel, db: &Surreal<Client>) -> Option<Channel> {
+    for id in channel.muted_from_list {
+        match search_channel_by_id(&id, db).await {
+            Some(muted_from_channel) => {
+                match unmute(&channel.username, &muted_from_channel.username, db).await {
+                    Some(_) => {}
+                    None => {
+                        eprintln!("Error: Can't Remove Muted From");
+                    }
+                }
+            }
+            None => {
+                eprintln!("Error: Can't Remove Muted From, Muted From Not Exists");
+            }
+        }
+    }
+    search_channel_by_username(&channel.username, db).await
+}
+pub async fn remove_all_blocked(channel: Channel, db: &Surreal<Client>) -> Option<Channel> {
+    for id in channel.blocked_list {
+        match search_channel_by_id(&id, db).await {
+            Some(blocked_channel) => {
+                match unblock(&channel.username, &blocked_channel.username, db).await {
+                    Some(_) => {}
+                    None => {
+                        eprintln!("Error: Can't Remove Blocked");
+                    }
+                }
+            }
+            None => {
+                eprintln!("Error: Can't Remove Blocked, Blocked Not Exists");
+            }
+        }
+    }
+    search_channel_by_username(&channel.username, db).await
+}
+pub async fn remove_all_moderators(channel: Channel, db: &Surreal<Client>) -> Option<Channel> {
+    for id in channel.moderator_list {
+        match search_channel_by_id(&id, db).await {
+            Some(moderator_channel) => {
+                match remove_moderator(&moderator_channel.username, &channel.username, db).await {
+                    Some(_) => {}
+                    None => {
+                        eprintln!("Error: Can't Remove Moderator");
+                    }
+                }
+            }
+            None => {
+                eprintln!("Error: Can't Remove Moderator, Moderator Not Exists");
+            }
+        }
+    }
+    search_channel_by_username(&channel.username, db).await
+}
+pub async fn remove_all_moderating(channel: Channel, db: &Surreal<Client>) -> Option<Channel> {
+    for id in channel.moderating_list {
+        match search_channel_by_id(&id, db).await {
+            Some(moderated_channel) => {
+                match remove_moderator(&channel.username, &moderated_channel.username, db).await {
+                    Some(_) => {}
+                    None => {
+                        eprintln!("Error: Can't Remove Moderating");
+                    }
+                }
+            }
+            None => {
+                eprintln!("Error: Can't Remove Moderating, Moderated Not Exists");
+            }
+        }
+    }
+    search_channel_by_username(&channel.username, db).await
+}
+pub async fn remove_all_members(channel: Channel, db: &Surreal<Client>) -> Option<Channel> {
+    for id in channel.member_list {
+        match search_channel_by_id(&id, db).await {
+            Some(member_channel) => {
+                match remove_member(&member_channel.username, &channel.username, db).await {
+                    Some(_) => {}
+                    None => {
+                        eprintln!("Error: Can't Remove Member");
+                    }
+                }
+            }
+            None => {
+                eprintln!("Error: Can't Remove Member, Member Not Exists");
+            }
+        }
+    }
+    search_channel_by_username(&channel.username, db).await
+}
+pub async fn remove_all_organizations(channel: Channel, db: &Surreal<Client>) -> Option<Channel> {
+    for id in channel.organization_list {
+        match search_channel_by_id(&id, db).await {
+            Some(organization_channel) => {
+                match remove_member(&channel.username, &organization_channel.username, db).await {
+                    Some(_) => {}
+                    None => {
+                        eprintln!("Error: Can't Remove Organization");
+                    }
+                }
+            }
+            None => {
+                eprintln!("Error: Can't Remove Organization, Organization Not Exists");
+            }
+        }
+    }
+    search_channel_by_username(&channel.username, db).await
+}
+pub async fn remove_all_blocked_from(channel: Channel, db: &Surreal<Client>) -> Option<Channel> {
+    for id in channel.blocked_from_list {
+        match search_channel_by_id(&id, db).await {
+            Some(blocked_from_channel) => {
+                match unblock(&blocked_from_channel.username, &channel.username, db).await {
+                    Some(_) => {}
+                    None => {
+                        eprintln!("Error: Can't Remove Blocked From");
+                    }
+                }
+            }
+            None => {
+                eprintln!("Error: Can't Remove Blocked From, Blocked From Not Exists");
+            }
+        }
+    }
+    search_channel_by_username(&channel.username, db).await
+}
 pub async fn is_follower_by_username(
     follower: &String,
     followed: &String,
@@ -501,25 +1861,619 @@ pub async fn is_follower_by_username(
         }
     }
 }
+pub async fn hydrate_list(ids: &[Id], db: &Surreal<Client>) -> Vec<Channel> {
+    let mut channels: HashMap<String, Channel> = search_channels_by_ids(ids, db)
+        .await
+        .into_iter()
+        .map(|channel| (id_to_string(&id_extractor(&channel)), channel))
+        .collect();
+    ids.iter()
+        .filter_map(|id| channels.remove(&id_to_string(id)))
+        .collect()
+}
 
-pub async fn is_banned_by_username(victim: &String, judge: &String, db: &Surreal<Client>) -> bool {
-    match search_channel_by_username(victim, db).await {
-        Some(victim_channel) => match search_channel_by_username(judge, db).await {
-            Some(mut judge_channel) => {
-                judge_channel.banned_list.sort();
-                judge_channel
-                    .banned_list
-                    .binary_search(&victim_channel.id.unwrap().id)
-                    .is_ok()
+/// Channel of a relation list with the key of the follow, ban or mute record
+/// stored for the relation.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RelationCandidate {
+    pub channel: Thing,
+    pub follow: Option<Thing>,
+    pub ban: Option<Thing>,
+    pub mute: Option<Thing>,
+    /// Organization the ban is inherited from.
+    pub inherited_from: Option<String>,
+}
+
+/// The `relation` list of `channel` with the keys of its records, banned
+/// lists also get the bans of the organizations of `channel` that it doesn't
+/// issue itself.
+pub async fn relation_candidates(
+    channel: &Channel,
+    relation: Relation,
+    db: &Surreal<Client>,
+) -> Vec<RelationCandidate> {
+    let channel_id = id_extractor(channel);
+    let mut candidates: Vec<RelationCandidate> = relation
+        .list(channel)
+        .iter()
+        .map(|other| {
+            let (follow, ban, mute) = match relation {
+                Relation::Follower => (Some(follow_thing(other, &channel_id)), None, None),
+                Relation::Followed => (Some(follow_thing(&channel_id, other)), None, None),
+                Relation::Banned => (None, Some(ban_thing(other, &channel_id)), None),
+                Relation::BannedFrom => (None, Some(ban_thing(&channel_id, other)), None),
+                Relation::Muted => (None, None, Some(mute_thing(other, &channel_id))),
+                Relation::MutedFrom => (None, None, Some(mute_thing(&channel_id, other))),
+                Relation::Blocked
+                | Relation::BlockedFrom
+                | Relation::Moderator
+                | Relation::Moderating
+                | Relation::Member
+                | Relation::Organization => (None, None, None),
+            };
+            RelationCandidate {
+                channel: Thing::from(("channel", other.clone())),
+                follow,
+                ban,
+                mute,
+                inherited_from: None,
             }
-            None => {
-                eprintln!("Error: Can't Check Is Banned | Judge Not Exists");
-                false
+        })
+        .collect();
+    if relation == Relation::Banned {
+        let mut seen: HashSet<String> = channel.banned_list.iter().map(id_to_string).collect();
+        for organization in hydrate_list(&channel.organization_list, db).await {
+            let organization_id = id_extractor(&organization);
+            for other in &organization.banned_list {
+                if seen.insert(id_to_string(other)) {
+                    candidates.push(RelationCandidate {
+                        channel: Thing::from(("channel", other.clone())),
+                        follow: None,
+                        ban: Some(ban_thing(other, &organization_id)),
+                        mute: None,
+                        inherited_from: Some(organization.username.clone()),
+                    });
+                }
+            }
+        }
+    }
+    candidates
+}
+
+/// Requesters waiting for the approval of `channel` with the keys of their
+/// requests.
+pub async fn follow_request_candidates(
+    channel: &Channel,
+    db: &Surreal<Client>,
+) -> Vec<RelationCandidate> {
+    match db
+        .query("SELECT follower AS channel, id AS follow FROM follow_request WHERE followed = $channel")
+        .bind(("channel", Thing::from(("channel", id_extractor(channel)))))
+        .await
+    {
+        Ok(mut response) => match response.take(0) {
+            Ok(candidates) => candidates,
+            Err(err_val) => {
+                eprintln!("Error: Follow Request Candidates | {}", err_val);
+                vec![]
             }
         },
+        Err(err_val) => {
+            eprintln!("Error: Follow Request Candidates | {}", err_val);
+            vec![]
+        }
+    }
+}
+
+/// Candidate loaded by `paginate` along with its sort keys.
+#[derive(Deserialize)]
+struct RelationRow {
+    channel: Channel,
+    follow: Option<Follow>,
+    ban: Option<Ban>,
+    mute: Option<Mute>,
+    inherited_from: Option<String>,
+    channel_id: String,
+    #[serde(default)]
+    name: String,
+    since: Option<Datetime>,
+}
+
+/// `next_cursor` of a page ending with `row`, its sort key then its id.
+fn relation_cursor(row: &RelationRow, sort: ListSort) -> String {
+    let key = match sort {
+        ListSort::Name => row.name.clone(),
+        ListSort::Time => row
+            .since
+            .as_ref()
+            .map(|since| since.to_raw())
+            .unwrap_or_default(),
+    };
+    format!("{}|{}", key, row.channel_id)
+}
+
+/// Sort key and id of a `relation_cursor`, `None` when it's malformed.
+pub fn parse_relation_cursor(cursor: &str, sort: ListSort) -> Option<(Value, String)> {
+    let (key, id) = cursor.rsplit_once('|')?;
+    let key = match sort {
+        ListSort::Name => Value::from(key),
+        ListSort::Time if key.is_empty() => Value::None,
+        ListSort::Time => Value::Datetime(Datetime::try_from(key).ok()?),
+    };
+    Some((key, id.to_string()))
+}
+
+/// Sorts `candidates` and cuts the page after `cursor` in the database,
+/// missing channels and expired inherited bans are left out. With
+/// `hide_bans`, bans of judges hiding them from victims are left out too
+/// unless `viewer` is the judge.
+///
+/// Pages are sorted by their key then by id and the cursor holds both, so
+/// the next page starts in place even when the last channel of the previous
+/// page is removed. Relations recorded before their timestamps existed sort
+/// as the oldest.
+pub async fn paginate(
+    candidates: Vec<RelationCandidate>,
+    cursor: Option<(Value, String)>,
+    pagination: &Pagination,
+    limit: usize,
+    hide_bans: bool,
+    viewer: Option<&String>,
+    db: &Surreal<Client>,
+) -> Option<Page<RelationEntry>> {
+    let key = match pagination.sort {
+        ListSort::Name => "name",
+        ListSort::Time => "since",
+    };
+    let (direction, after) = match pagination.order {
+        SortOrder::Asc => ("ASC", ">"),
+        SortOrder::Desc => ("DESC", "<"),
+    };
+    let after_cursor = match cursor {
+        Some(_) => format!(
+            "WHERE {key} {after} $cursor_key OR ({key} = $cursor_key AND channel_id {after} $cursor_id)"
+        ),
+        None => String::new(),
+    };
+    let (cursor_key, cursor_id) = cursor.unzip();
+    match db
+        .query("LET $items = (SELECT channel.* AS channel, follow.* AS follow, ban.* AS ban, mute.* AS mute, inherited_from, meta::id(channel) AS channel_id, channel.normalized_username AS name, follow.created_at ?? ban.created_at ?? mute.created_at AS since FROM $candidates)")
+        .query("LET $listed = (SELECT * FROM $items WHERE channel != NONE AND (inherited_from = NONE OR ban.expires_at = NONE OR ban.expires_at > time::now()) AND ($hide_bans = false OR channel.settings.bans_visible_to_victim != false OR channel.normalized_username = $viewer))")
+        .query("RETURN array::len($listed)")
+        .query(format!(
+            "SELECT * FROM $listed {after_cursor} ORDER BY {key} {direction}, channel_id {direction} LIMIT $limit"
+        ))
+        .bind(("candidates", candidates))
+        .bind(("hide_bans", hide_bans))
+        .bind(("viewer", viewer.map(|viewer| normalize_username(viewer))))
+        .bind(("cursor_key", cursor_key.unwrap_or_default()))
+        .bind(("cursor_id", cursor_id))
+        .bind(("limit", limit + 1))
+        .await
+    {
+        Ok(mut response) => {
+            let total: Option<usize> = response.take(2).unwrap_or_default();
+            let mut rows: Vec<RelationRow> = match response.take(3) {
+                Ok(rows) => rows,
+                Err(err_val) => {
+                    eprintln!("Error: Paginate | {}", err_val);
+                    return None;
+                }
+            };
+            let next_cursor = match rows.len() > limit {
+                true => {
+                    rows.truncate(limit);
+                    rows.last().map(|row| relation_cursor(row, pagination.sort))
+                }
+                false => None,
+            };
+            Some(Page {
+                total: total.unwrap_or_default(),
+                items: rows.into_iter().map(relation_entry).collect(),
+                next_cursor,
+            })
+        }
+        Err(err_val) => {
+            eprintln!("Error: Paginate | {}", err_val);
+            None
+        }
+    }
+}
+
+fn relation_entry(row: RelationRow) -> RelationEntry {
+    let (reason, moderator, notes, expires_at, scope) = match (row.ban, row.mute) {
+        (Some(ban), _) => (
+            ban.reason,
+            ban.moderator,
+            ban.notes,
+            ban.expires_at,
+            Some(ban.scope),
+        ),
+        (None, Some(mute)) => (None, mute.moderator, None, mute.expires_at, None),
+        (None, None) => (None, None, None, None, None),
+    };
+    RelationEntry {
+        id: row.channel_id,
+        username: row.channel.username,
+        since: row.since.map(|since| since.to_raw()),
+        source: row.follow.and_then(|follow| follow.source),
+        reason,
+        moderator,
+        notes,
+        expires_at: expires_at.map(|expires_at| expires_at.to_raw()),
+        inherited_from: row.inherited_from,
+        scope,
+    }
+}
+
+/// Bans are the effective ones of `ban_scope_in`, details are only given for
+/// direct bans. Bans of judges hiding them from victims are left out of
+/// `banned_by`.
+pub fn relationship_between(records: &RelationshipRecords) -> Relationship {
+    let (channel, other) = (&records.channel, &records.other);
+    let channel_id = id_extractor(channel);
+    let other_id = id_extractor(other);
+    let since = |follower: &Id, followed: &Id| {
+        records
+            .follows
+            .iter()
+            .find(|follow| follow.follower.id == *follower && follow.followed.id == *followed)
+            .map(|follow| follow.created_at.to_raw())
+    };
+    let ban = |victim: &Id, judge: &Id| {
+        records
+            .bans
+            .bans
+            .iter()
+            .find(|ban| ban.victim.id == *victim && ban.judge.id == *judge)
+    };
+    let ban_info = |ban: &Ban| BanInfo {
+        since: ban.created_at.to_raw(),
+        reason: ban.reason.clone(),
+        moderator: ban.moderator.clone(),
+        notes: ban.notes.clone(),
+        expires_at: ban
+            .expires_at
+            .as_ref()
+            .map(|expires_at| expires_at.to_raw()),
+        scope: ban.scope,
+    };
+    let is_active = |ban: Option<&Ban>| !ban.is_some_and(|ban| ban.is_expired());
+    let follows = channel.followed_list.contains(&other_id);
+    let followed_by = channel.follower_list.contains(&other_id);
+    let direct_ban = |victim: &Id, judge: &Channel, judge_id: &Id| {
+        ban(victim, judge_id)
+            .filter(|ban| judge.banned_list.contains(victim) && is_active(Some(ban)))
+    };
+    let banned_scope = ban_scope_in(other, channel, &records.bans);
+    let banned_by_scope = ban_scope_in(channel, other, &records.bans)
+        .filter(|_| other.settings.bans_visible_to_victim);
+    let mute_is_active = |victim: &Id, judge: &Id| {
+        !records
+            .mutes
+            .iter()
+            .any(|mute| mute.victim.id == *victim && mute.judge.id == *judge && mute.is_expired())
+    };
+    Relationship {
+        follows,
+        follows_since: since(&channel_id, &other_id).filter(|_| follows),
+        followed_by,
+        followed_by_since: since(&other_id, &channel_id).filter(|_| followed_by),
+        banned: banned_scope.is_some(),
+        banned_scope,
+        banned_details: direct_ban(&other_id, channel, &channel_id).map(ban_info),
+        banned_by: banned_by_scope.is_some(),
+        banned_by_scope,
+        banned_by_details: direct_ban(&channel_id, other, &other_id)
+            .filter(|_| other.settings.bans_visible_to_victim)
+            .map(ban_info),
+        muted: channel.muted_list.contains(&other_id) && mute_is_active(&other_id, &channel_id),
+        muted_by: other.muted_list.contains(&channel_id) && mute_is_active(&channel_id, &other_id),
+        blocked: channel.blocked_list.contains(&other_id),
+        blocked_by: other.blocked_list.contains(&channel_id),
+    }
+}
+
+pub async fn is_in_relation_by_usernames(
+    username: &String,
+    relation: Relation,
+    usernames: &[String],
+    db: &Surreal<Client>,
+) -> Option<BTreeMap<String, bool>> {
+    match search_channel_by_username(username, db).await {
+        Some(channel) => {
+            let others: HashMap<String, Channel> = search_channels_by_usernames(usernames, db)
+                .await
+                .into_iter()
+                .map(|other| (normalize_username(&other.username), other))
+                .collect();
+            let expired = expired_among(&channel, relation, others.values(), db).await;
+            let results = usernames
+                .iter()
+                .map(|username| {
+                    let contains = match others.get(&normalize_username(username)) {
+                        Some(other) => {
+                            let other_id = id_extractor(other);
+                            relation.list(&channel).contains(&other_id)
+                                && !expired.contains(&id_to_string(&other_id))
+                        }
+                        None => false,
+                    };
+                    (username.clone(), contains)
+                })
+                .collect();
+            Some(results)
+        }
         None => {
-            eprintln!("Error: Can't Check Is Banned | Victim Not Exists");
-            false
+            eprintln!("Error: Can't Check Batch | Channel Not Exists");
+            None
+        }
+    }
+}
+
+/// Ids of the `others` whose ban or mute in `relation` with `channel` has
+/// expired.
+async fn expired_among(
+    channel: &Channel,
+    relation: Relation,
+    others: impl Iterator<Item = &Channel>,
+    db: &Surreal<Client>,
+) -> HashSet<String> {
+    let channel_id = id_extractor(channel);
+    let pairs: Vec<(Id, Id)> = match relation {
+        Relation::Banned | Relation::Muted => others
+            .map(|other| (id_extractor(other), channel_id.clone()))
+            .collect(),
+        Relation::BannedFrom | Relation::MutedFrom => others
+            .map(|other| (channel_id.clone(), id_extractor(other)))
+            .collect(),
+        Relation::Follower
+        | Relation::Followed
+        | Relation::Blocked
+        | Relation::BlockedFrom
+        | Relation::Moderator
+        | Relation::Moderating
+        | Relation::Member
+        | Relation::Organization => return HashSet::new(),
+    };
+    let expired: Vec<(Thing, Thing)> = match relation {
+        Relation::Banned | Relation::BannedFrom => search_records::<Ban>(
+            pairs
+                .iter()
+                .map(|(victim, judge)| ban_thing(victim, judge))
+                .collect(),
+            db,
+        )
+        .await
+        .into_iter()
+        .filter(|ban| ban.is_expired())
+        .map(|ban| (ban.victim, ban.judge))
+        .collect(),
+        _ => search_records::<Mute>(
+            pairs
+                .iter()
+                .map(|(victim, judge)| mute_thing(victim, judge))
+                .collect(),
+            db,
+        )
+        .await
+        .into_iter()
+        .filter(|mute| mute.is_expired())
+        .map(|mute| (mute.victim, mute.judge))
+        .collect(),
+    };
+    expired
+        .into_iter()
+        .map(|(victim, judge)| match relation {
+            Relation::Banned | Relation::Muted => id_to_string(&victim.id),
+            _ => id_to_string(&judge.id),
+        })
+        .collect()
+}
+
+fn already_error(relation: Relation) -> ApiError {
+    match relation {
+        Relation::Follower | Relation::Followed => ApiError::AlreadyFollower,
+        Relation::Banned | Relation::BannedFrom => ApiError::AlreadyBanned,
+        Relation::Muted | Relation::MutedFrom => ApiError::AlreadyMuted,
+        Relation::Blocked | Relation::BlockedFrom => ApiError::AlreadyBlocked,
+        Relation::Moderator | Relation::Moderating => ApiError::AlreadyModerator,
+        Relation::Member | Relation::Organization => ApiError::AlreadyMember,
+    }
+}
+
+fn missing_error(relation: Relation) -> ApiError {
+    match relation {
+        Relation::Follower | Relation::Followed => ApiError::NotFollower,
+        Relation::Banned | Relation::BannedFrom => ApiError::NotBanned,
+        Relation::Muted | Relation::MutedFrom => ApiError::NotMuted,
+        Relation::Blocked | Relation::BlockedFrom => ApiError::NotBlocked,
+        Relation::Moderator | Relation::Moderating => ApiError::NotModerator,
+        Relation::Member | Relation::Organization => ApiError::NotMember,
+    }
+}
+
+pub fn has_relation(channel: &Channel, relation: Relation, other: &Channel) -> bool {
+    relation.list(channel).contains(&id_extractor(other))
+}
+
+/// `victim` is in the ban list of `judge` and the ban hasn't expired.
+pub async fn is_actively_banned(victim: &Channel, judge: &Channel, db: &Surreal<Client>) -> bool {
+    has_relation(judge, Relation::Banned, victim)
+        && !search_ban(victim, judge, db)
+            .await
+            .is_some_and(|ban| ban.is_expired())
+}
+
+/// Records the effective ban between channels is computed from, see
+/// `ban_scope_in`.
+#[derive(Debug, Default)]
+pub struct BanRecords {
+    pub global_bans: Vec<GlobalBan>,
+    pub ban_lists: Vec<BanList>,
+    pub organizations: Vec<Channel>,
+    pub bans: Vec<Ban>,
+}
+
+/// Loads in one round trip the records the effective bans of every channel
+/// of `victims` by every channel of `judges` depend on.
+pub async fn search_ban_records(
+    victims: &[Channel],
+    judges: &[Channel],
+    db: &Surreal<Client>,
+) -> BanRecords {
+    let global_bans: Vec<Thing> = victims
+        .iter()
+        .map(|victim| global_ban_thing(&id_extractor(victim)))
+        .collect();
+    let ban_lists: Vec<Thing> = judges
+        .iter()
+        .flat_map(|judge| judge.ban_list_subscriptions.iter().cloned())
+        .collect();
+    let organization_ids: Vec<Id> = judges
+        .iter()
+        .flat_map(|judge| judge.organization_list.iter().cloned())
+        .collect();
+    let organizations: Vec<Thing> = organization_ids
+        .iter()
+        .map(|id| Thing::from(("channel", id.clone())))
+        .collect();
+    let judge_ids: Vec<Id> = judges
+        .iter()
+        .map(id_extractor)
+        .chain(organization_ids)
+        .collect();
+    let bans: Vec<Thing> = victims
+        .iter()
+        .flat_map(|victim| {
+            let victim_id = id_extractor(victim);
+            judge_ids
+                .iter()
+                .map(move |judge_id| ban_thing(&victim_id, judge_id))
+        })
+        .collect();
+    match db
+        .query("SELECT * FROM $global_bans; SELECT * FROM $ban_lists; SELECT * FROM $organizations; SELECT * FROM $bans")
+        .bind(("global_bans", global_bans))
+        .bind(("ban_lists", ban_lists))
+        .bind(("organizations", organizations))
+        .bind(("bans", bans))
+        .await
+    {
+        Ok(mut response) => BanRecords {
+            global_bans: response.take(0).unwrap_or_default(),
+            ban_lists: response.take(1).unwrap_or_default(),
+            organizations: response.take(2).unwrap_or_default(),
+            bans: response.take(3).unwrap_or_default(),
+        },
+        Err(err_val) => {
+            eprintln!("Error: Search Ban Records | {}", err_val);
+            BanRecords::default()
+        }
+    }
+}
+
+/// Widest scope `victim` is banned from `judge` with according to `records`.
+/// Global bans and subscribed ban lists ban fully, bans of the judge's
+/// organizations keep their scope and bans without a record are full.
+pub fn ban_scope_in(victim: &Channel, judge: &Channel, records: &BanRecords) -> Option<BanScope> {
+    let victim_id = id_extractor(victim);
+    let judge_id = id_extractor(judge);
+    let globally_banned = records
+        .global_bans
+        .iter()
+        .any(|global_ban| global_ban.channel.id == victim_id && !global_ban.is_expired());
+    let ban_listed = !judge.allowed_list.contains(&victim_id)
+        && records.ban_lists.iter().any(|ban_list| {
+            ban_list
+                .id
+                .as_ref()
+                .is_some_and(|id| judge.ban_list_subscriptions.contains(id))
+                && (ban_list.published || ban_list.owner.id == judge_id)
+                && ban_list.entry_list.contains(&victim_id)
+        });
+    if globally_banned || ban_listed {
+        return Some(BanScope::Full);
+    }
+    let active_scope = |judge: &Channel| {
+        if !has_relation(judge, Relation::Banned, victim) {
+            return None;
+        }
+        let judge_id = id_extractor(judge);
+        match records
+            .bans
+            .iter()
+            .find(|ban| ban.victim.id == victim_id && ban.judge.id == judge_id)
+        {
+            Some(ban) if ban.is_expired() => None,
+            Some(ban) => Some(ban.scope),
+            None => Some(BanScope::Full),
+        }
+    };
+    records
+        .organizations
+        .iter()
+        .filter(|organization| {
+            judge
+                .organization_list
+                .contains(&id_extractor(organization))
+        })
+        .fold(active_scope(judge), |scope, organization| {
+            scope.max(active_scope(organization))
+        })
+}
+
+/// Widest scope `victim` is banned from `judge` with, see `ban_scope_in`.
+pub async fn effective_ban_scope(
+    victim: &Channel,
+    judge: &Channel,
+    db: &Surreal<Client>,
+) -> Option<BanScope> {
+    let records = search_ban_records(
+        std::slice::from_ref(victim),
+        std::slice::from_ref(judge),
+        db,
+    )
+    .await;
+    ban_scope_in(victim, judge, &records)
+}
+
+/// `effective` when it covers `scope`, any ban counts when `scope` is `None`.
+pub fn covering_scope(effective: Option<BanScope>, scope: Option<BanScope>) -> Option<BanScope> {
+    effective.filter(|effective| scope.is_none_or(|scope| effective.covers(scope)))
+}
+
+/// An organization `judge` is a member of has `victim` actively banned.
+pub async fn is_banned_by_organizations(
+    victim: &Channel,
+    judge: &Channel,
+    db: &Surreal<Client>,
+) -> bool {
+    for organization in hydrate_list(&judge.organization_list, db).await {
+        if is_actively_banned(victim, &organization, db).await {
+            return true;
         }
     }
+    false
+}
+
+/// Either channel has blocked the other one.
+pub fn is_blocked_between(channel: &Channel, other: &Channel) -> bool {
+    has_relation(channel, Relation::Blocked, other)
+        || has_relation(other, Relation::Blocked, channel)
+}
+
+/// Checks that `other` is not yet (`add`) or still (`!add`) in the
+/// `relation` list of `channel`.
+pub fn check_relation(
+    channel: &Channel,
+    relation: Relation,
+    other: &Channel,
+    add: bool,
+) -> Result<(), ApiError> {
+    match (add, has_relation(channel, relation, other)) {
+        (true, true) => Err(already_error(relation)),
+        (false, false) => Err(missing_error(relation)),
+        _ => Ok(()),
+    }
 }
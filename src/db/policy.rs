@@ -0,0 +1,187 @@
+use std::sync::{Arc, RwLock};
+
+use axum::async_trait;
+use surrealdb::{engine::remote::ws::Client, Surreal};
+
+use crate::{error::ApiError, utils::api_config, AcceptFollows, BanScope, Channel};
+
+use super::db_utils::{effective_ban_scope, is_blocked_between, is_globally_banned_channel};
+
+static POLICIES: RwLock<Vec<Arc<dyn RelationshipPolicy>>> = RwLock::new(Vec::new());
+
+/// Relation a channel (`actor`) tries to start towards another (`target`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Follow,
+    Ban,
+    Mute,
+    Block,
+    Moderate,
+    AddMember,
+}
+
+/// Rule consulted before a relation is created, rejections are returned to
+/// the client as they are.
+///
+/// Custom rules are added with `register_policy` and run after the built-in
+/// ones.
+#[async_trait]
+pub trait RelationshipPolicy: Send + Sync {
+    async fn check(
+        &self,
+        action: Action,
+        actor: &Channel,
+        target: &Channel,
+        db: &Surreal<Client>,
+    ) -> Result<(), ApiError>;
+}
+
+pub fn register_policy<P: RelationshipPolicy + 'static>(policy: P) {
+    POLICIES.write().unwrap().push(Arc::new(policy));
+}
+
+/// Runs the built-in rules, then the registered ones, stopping at the first
+/// rejection.
+pub async fn check_policies(
+    action: Action,
+    actor: &Channel,
+    target: &Channel,
+    db: &Surreal<Client>,
+) -> Result<(), ApiError> {
+    let registered = POLICIES.read().unwrap().clone();
+    let built_in: [Arc<dyn RelationshipPolicy>; 6] = [
+        Arc::new(NoSelfRelation),
+        Arc::new(GloballyBannedCannotAct),
+        Arc::new(FollowsClosed),
+        Arc::new(MaxFollowedCount),
+        Arc::new(BannedCannotFollow),
+        Arc::new(BlockedCannotFollow),
+    ];
+    for policy in built_in.iter().chain(registered.iter()) {
+        policy.check(action, actor, target, db).await?;
+    }
+    Ok(())
+}
+
+/// Channels can't follow, ban, mute, block or moderate themselves.
+pub struct NoSelfRelation;
+
+#[async_trait]
+impl RelationshipPolicy for NoSelfRelation {
+    async fn check(
+        &self,
+        _action: Action,
+        actor: &Channel,
+        target: &Channel,
+        _db: &Surreal<Client>,
+    ) -> Result<(), ApiError> {
+        match actor.id == target.id {
+            true => Err(ApiError::SelfRelation),
+            false => Ok(()),
+        }
+    }
+}
+
+/// Globally banned channels can't start any relation.
+pub struct GloballyBannedCannotAct;
+
+#[async_trait]
+impl RelationshipPolicy for GloballyBannedCannotAct {
+    async fn check(
+        &self,
+        _action: Action,
+        actor: &Channel,
+        _target: &Channel,
+        db: &Surreal<Client>,
+    ) -> Result<(), ApiError> {
+        match is_globally_banned_channel(actor, db).await {
+            true => Err(ApiError::GloballyBanned),
+            false => Ok(()),
+        }
+    }
+}
+
+/// Channels accepting follows from nobody can't be followed.
+pub struct FollowsClosed;
+
+#[async_trait]
+impl RelationshipPolicy for FollowsClosed {
+    async fn check(
+        &self,
+        action: Action,
+        _actor: &Channel,
+        target: &Channel,
+        _db: &Surreal<Client>,
+    ) -> Result<(), ApiError> {
+        match action == Action::Follow && target.settings.accept_follows == AcceptFollows::Nobody {
+            true => Err(ApiError::FollowsClosed),
+            false => Ok(()),
+        }
+    }
+}
+
+/// Channels can follow at most `max_followed_count` channels, 0 is unlimited.
+pub struct MaxFollowedCount;
+
+#[async_trait]
+impl RelationshipPolicy for MaxFollowedCount {
+    async fn check(
+        &self,
+        action: Action,
+        actor: &Channel,
+        _target: &Channel,
+        _db: &Surreal<Client>,
+    ) -> Result<(), ApiError> {
+        let max_followed_count = api_config().max_followed_count;
+        match action == Action::Follow
+            && max_followed_count > 0
+            && actor.followed_list.len() >= max_followed_count
+        {
+            true => Err(ApiError::MaxFollowed),
+            false => Ok(()),
+        }
+    }
+}
+
+/// Channels can't follow a channel that fully banned them, lists them in a
+/// subscribed ban list or belongs to an organization that fully banned them,
+/// when `banned_cannot_follow` is set. Chat bans still allow following.
+pub struct BannedCannotFollow;
+
+#[async_trait]
+impl RelationshipPolicy for BannedCannotFollow {
+    async fn check(
+        &self,
+        action: Action,
+        actor: &Channel,
+        target: &Channel,
+        db: &Surreal<Client>,
+    ) -> Result<(), ApiError> {
+        match action == Action::Follow
+            && api_config().banned_cannot_follow
+            && effective_ban_scope(actor, target, db).await == Some(BanScope::Full)
+        {
+            true => Err(ApiError::BannedByChannel),
+            false => Ok(()),
+        }
+    }
+}
+
+/// Channels can't follow each other while either one blocks the other.
+pub struct BlockedCannotFollow;
+
+#[async_trait]
+impl RelationshipPolicy for BlockedCannotFollow {
+    async fn check(
+        &self,
+        action: Action,
+        actor: &Channel,
+        target: &Channel,
+        _db: &Surreal<Client>,
+    ) -> Result<(), ApiError> {
+        match action == Action::Follow && is_blocked_between(actor, target) {
+            true => Err(ApiError::Blocked),
+            false => Ok(()),
+        }
+    }
+}
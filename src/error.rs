@@ -0,0 +1,254 @@
+use std::fmt;
+
+use axum::{extract::rejection::JsonRejection, http::StatusCode, response::IntoResponse, Json};
+
+/// Broken rule of an input field, e.g. `username_too_long` on `username`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    pub field: &'static str,
+    pub code: &'static str,
+    pub message: String,
+}
+
+/// Rejection of a custom relationship policy, e.g. `follow_limit_reached`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyViolation {
+    pub code: &'static str,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiError {
+    MalformedBody(String),
+    InvalidBody(String),
+    EmptyField(&'static str),
+    Validation(ValidationError),
+    ChannelNotExists,
+    ChannelAlreadyExists,
+    UsernameConfusable,
+    UsernameQuarantined,
+    RenameCooldown,
+    AlreadyFollower,
+    NotFollower,
+    AlreadyBanned,
+    NotBanned,
+    AlreadyMuted,
+    NotMuted,
+    AlreadyBlocked,
+    NotBlocked,
+    AlreadyModerator,
+    NotModerator,
+    AlreadyMember,
+    NotMember,
+    AlreadyRequested,
+    NotRequested,
+    Blocked,
+    BannedByChannel,
+    FollowsClosed,
+    GloballyBanned,
+    AlreadyGloballyBanned,
+    NotGloballyBanned,
+    BanListNotExists,
+    BanListAlreadyExists,
+    BanListNotPublished,
+    AlreadyInBanList,
+    NotInBanList,
+    AlreadySubscribed,
+    NotSubscribed,
+    AlreadyAllowed,
+    NotAllowed,
+    Unauthorized,
+    ModeratorRequired,
+    ListHidden,
+    SelfRelation,
+    MaxFollowed,
+    PolicyViolation(PolicyViolation),
+    Database,
+}
+
+impl ApiError {
+    /// Stable machine readable code, part of the public API.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::MalformedBody(_) => "malformed_body",
+            ApiError::InvalidBody(_) => "invalid_body",
+            ApiError::EmptyField(_) => "empty_field",
+            ApiError::Validation(validation) => validation.code,
+            ApiError::ChannelNotExists => "not_found",
+            ApiError::ChannelAlreadyExists => "already_exists",
+            ApiError::UsernameConfusable => "username_confusable",
+            ApiError::UsernameQuarantined => "username_quarantined",
+            ApiError::RenameCooldown => "rename_cooldown",
+            ApiError::AlreadyFollower => "already_follower",
+            ApiError::NotFollower => "not_follower",
+            ApiError::AlreadyBanned => "already_banned",
+            ApiError::NotBanned => "not_banned",
+            ApiError::AlreadyMuted => "already_muted",
+            ApiError::NotMuted => "not_muted",
+            ApiError::AlreadyBlocked => "already_blocked",
+            ApiError::NotBlocked => "not_blocked",
+            ApiError::AlreadyModerator => "already_moderator",
+            ApiError::NotModerator => "not_moderator",
+            ApiError::AlreadyMember => "already_member",
+            ApiError::NotMember => "not_member",
+            ApiError::AlreadyRequested => "already_requested",
+            ApiError::NotRequested => "not_requested",
+            ApiError::Blocked => "blocked",
+            ApiError::BannedByChannel => "banned_by_channel",
+            ApiError::FollowsClosed => "follows_closed",
+            ApiError::GloballyBanned => "globally_banned",
+            ApiError::AlreadyGloballyBanned => "already_globally_banned",
+            ApiError::NotGloballyBanned => "not_globally_banned",
+            ApiError::BanListNotExists => "ban_list_not_found",
+            ApiError::BanListAlreadyExists => "ban_list_already_exists",
+            ApiError::BanListNotPublished => "ban_list_not_published",
+            ApiError::AlreadyInBanList => "already_in_ban_list",
+            ApiError::NotInBanList => "not_in_ban_list",
+            ApiError::AlreadySubscribed => "already_subscribed",
+            ApiError::NotSubscribed => "not_subscribed",
+            ApiError::AlreadyAllowed => "already_allowed",
+            ApiError::NotAllowed => "not_allowed",
+            ApiError::Unauthorized => "unauthorized",
+            ApiError::ModeratorRequired => "moderator_required",
+            ApiError::ListHidden => "list_hidden",
+            ApiError::SelfRelation => "self_relation",
+            ApiError::MaxFollowed => "max_followed",
+            ApiError::PolicyViolation(violation) => violation.code,
+            ApiError::Database => "database_error",
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::MalformedBody(_) => StatusCode::BAD_REQUEST,
+            ApiError::InvalidBody(_)
+            | ApiError::EmptyField(_)
+            | ApiError::Validation(_)
+            | ApiError::SelfRelation => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::ChannelNotExists | ApiError::BanListNotExists => StatusCode::NOT_FOUND,
+            ApiError::ChannelAlreadyExists
+            | ApiError::UsernameConfusable
+            | ApiError::UsernameQuarantined
+            | ApiError::AlreadyFollower
+            | ApiError::NotFollower
+            | ApiError::AlreadyBanned
+            | ApiError::NotBanned
+            | ApiError::AlreadyMuted
+            | ApiError::NotMuted
+            | ApiError::AlreadyBlocked
+            | ApiError::NotBlocked
+            | ApiError::AlreadyModerator
+            | ApiError::NotModerator
+            | ApiError::AlreadyMember
+            | ApiError::NotMember
+            | ApiError::AlreadyGloballyBanned
+            | ApiError::NotGloballyBanned
+            | ApiError::BanListAlreadyExists
+            | ApiError::AlreadyInBanList
+            | ApiError::NotInBanList
+            | ApiError::AlreadySubscribed
+            | ApiError::NotSubscribed
+            | ApiError::AlreadyAllowed
+            | ApiError::NotAllowed
+            | ApiError::AlreadyRequested
+            | ApiError::NotRequested => StatusCode::CONFLICT,
+            ApiError::Blocked
+            | ApiError::BannedByChannel
+            | ApiError::FollowsClosed
+            | ApiError::GloballyBanned
+            | ApiError::BanListNotPublished
+            | ApiError::ModeratorRequired
+            | ApiError::ListHidden
+            | ApiError::MaxFollowed
+            | ApiError::PolicyViolation(_) => StatusCode::FORBIDDEN,
+            ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::RenameCooldown => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::Database => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            ApiError::MalformedBody(reason) => return write!(f, "Malformed Body | {}", reason),
+            ApiError::InvalidBody(reason) => return write!(f, "Invalid Body | {}", reason),
+            ApiError::EmptyField(field) => return write!(f, "Empty Field | {}", field),
+            ApiError::Validation(validation) => return write!(f, "{}", validation.message),
+            ApiError::PolicyViolation(violation) => return write!(f, "{}", violation.message),
+            ApiError::ChannelNotExists => "Channel Not Exists",
+            ApiError::ChannelAlreadyExists => "Channel Already Exists",
+            ApiError::UsernameConfusable => "Username Confusable With Another Channel",
+            ApiError::UsernameQuarantined => "Username Recently Used By Another Channel",
+            ApiError::RenameCooldown => "Username Changed Too Recently",
+            ApiError::AlreadyFollower => "Already Follower",
+            ApiError::NotFollower => "Not Follower",
+            ApiError::AlreadyBanned => "Already Banned",
+            ApiError::NotBanned => "Not Banned",
+            ApiError::AlreadyMuted => "Already Muted",
+            ApiError::NotMuted => "Not Muted",
+            ApiError::AlreadyBlocked => "Already Blocked",
+            ApiError::NotBlocked => "Not Blocked",
+            ApiError::AlreadyModerator => "Already Moderator",
+            ApiError::NotModerator => "Not Moderator",
+            ApiError::AlreadyMember => "Already Member",
+            ApiError::NotMember => "Not Member",
+            ApiError::AlreadyRequested => "Already Requested",
+            ApiError::NotRequested => "Not Requested",
+            ApiError::Blocked => "Blocked",
+            ApiError::BannedByChannel => "Banned By Channel",
+            ApiError::FollowsClosed => "Channel Doesn't Accept Follows",
+            ApiError::GloballyBanned => "Globally Banned",
+            ApiError::AlreadyGloballyBanned => "Already Globally Banned",
+            ApiError::NotGloballyBanned => "Not Globally Banned",
+            ApiError::BanListNotExists => "Ban List Not Exists",
+            ApiError::BanListAlreadyExists => "Ban List Already Exists",
+            ApiError::BanListNotPublished => "Ban List Not Published",
+            ApiError::AlreadyInBanList => "Already In Ban List",
+            ApiError::NotInBanList => "Not In Ban List",
+            ApiError::AlreadySubscribed => "Already Subscribed",
+            ApiError::NotSubscribed => "Not Subscribed",
+            ApiError::AlreadyAllowed => "Already Allowed",
+            ApiError::NotAllowed => "Not Allowed",
+            ApiError::Unauthorized => "Unauthorized",
+            ApiError::ModeratorRequired => "Moderator Of Channel Required",
+            ApiError::ListHidden => "List Hidden",
+            ApiError::SelfRelation => "Self Relation",
+            ApiError::MaxFollowed => "Followed Limit Reached",
+            ApiError::Database => "Database Error",
+        };
+        write!(f, "{}", message)
+    }
+}
+
+impl From<ValidationError> for ApiError {
+    fn from(validation: ValidationError) -> Self {
+        ApiError::Validation(validation)
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        match rejection {
+            JsonRejection::JsonDataError(_) => ApiError::InvalidBody(rejection.body_text()),
+            _ => ApiError::MalformedBody(rejection.body_text()),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> axum::response::Response {
+        let error = match &self {
+            ApiError::Validation(validation) => serde_json::json!({
+                "error":self.code(),
+                "message":self.to_string(),
+                "field":validation.field,
+            }),
+            _ => serde_json::json!({
+                "error":self.code(),
+                "message":self.to_string(),
+            }),
+        };
+        (self.status(), Json(error)).into_response()
+    }
+}
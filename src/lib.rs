@@ -1,15 +1,22 @@
-use std::net::IpAddr;
+// Usernames are passed around as `&String` throughout the crate.
+#![allow(clippy::ptr_arg)]
+
+use std::{collections::BTreeMap, net::IpAddr};
 
 use serde::{Deserialize, Serialize};
 use surrealdb::{
     engine::remote::ws::Client,
-    sql::{Id, Thing},
+    sql::{Datetime, Id, Thing},
     Surreal,
 };
 
 pub mod db;
+pub mod error;
+pub mod request;
+pub mod response;
 pub mod routing;
 pub mod tests;
+pub mod username;
 pub mod utils;
 
 #[derive(Debug, Clone)]
@@ -27,6 +34,184 @@ pub struct ServerConfig {
     pub port: u16,
 }
 
+/// `Debug` leaves `admin_token` out, the config is printed at startup.
+#[derive(Clone)]
+pub struct ApiConfig {
+    pub max_batch_size: usize,
+    pub batch_chunk_size: usize,
+    pub username_rules: UsernameRules,
+    pub confusable_detection: bool,
+    pub username_quarantine_seconds: u64,
+    pub rename_cooldown_seconds: u64,
+    pub require_ban_reason: bool,
+    pub ban_expiry_interval_seconds: u64,
+    /// Banning removes the victim's follow of the judge.
+    pub ban_removes_follow: bool,
+    /// Banned channels can't follow the channel that banned them.
+    pub banned_cannot_follow: bool,
+    /// Most channels one channel can follow, 0 is unlimited.
+    pub max_followed_count: usize,
+    /// Expected in the `x-admin-token` header of admin URLs, admin URLs are
+    /// disabled while empty.
+    pub admin_token: String,
+}
+
+impl std::fmt::Debug for ApiConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let admin_token = match self.admin_token.is_empty() {
+            true => "",
+            false => "<redacted>",
+        };
+        f.debug_struct("ApiConfig")
+            .field("max_batch_size", &self.max_batch_size)
+            .field("batch_chunk_size", &self.batch_chunk_size)
+            .field("username_rules", &self.username_rules)
+            .field("confusable_detection", &self.confusable_detection)
+            .field(
+                "username_quarantine_seconds",
+                &self.username_quarantine_seconds,
+            )
+            .field("rename_cooldown_seconds", &self.rename_cooldown_seconds)
+            .field("require_ban_reason", &self.require_ban_reason)
+            .field(
+                "ban_expiry_interval_seconds",
+                &self.ban_expiry_interval_seconds,
+            )
+            .field("ban_removes_follow", &self.ban_removes_follow)
+            .field("banned_cannot_follow", &self.banned_cannot_follow)
+            .field("max_followed_count", &self.max_followed_count)
+            .field("admin_token", &admin_token)
+            .finish()
+    }
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        ApiConfig {
+            max_batch_size: 100,
+            batch_chunk_size: 25,
+            username_rules: UsernameRules::default(),
+            confusable_detection: false,
+            username_quarantine_seconds: 2_592_000,
+            rename_cooldown_seconds: 86_400,
+            require_ban_reason: false,
+            ban_expiry_interval_seconds: 60,
+            ban_removes_follow: true,
+            banned_cannot_follow: true,
+            max_followed_count: 0,
+            admin_token: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CharacterClass {
+    Letter,
+    Digit,
+    Underscore,
+    Hyphen,
+    Period,
+}
+
+impl CharacterClass {
+    pub fn from_name(name: &str) -> Option<CharacterClass> {
+        match name {
+            "letter" => Some(CharacterClass::Letter),
+            "digit" => Some(CharacterClass::Digit),
+            "underscore" => Some(CharacterClass::Underscore),
+            "hyphen" => Some(CharacterClass::Hyphen),
+            "period" => Some(CharacterClass::Period),
+            _ => None,
+        }
+    }
+
+    pub fn contains(self, character: char) -> bool {
+        match self {
+            CharacterClass::Letter => character.is_alphabetic(),
+            CharacterClass::Digit => character.is_numeric(),
+            CharacterClass::Underscore => character == '_',
+            CharacterClass::Hyphen => character == '-',
+            CharacterClass::Period => character == '.',
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UsernameRules {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub allowed_characters: Vec<CharacterClass>,
+    pub reserved: Vec<String>,
+}
+
+impl Default for UsernameRules {
+    fn default() -> Self {
+        UsernameRules {
+            min_length: 3,
+            max_length: 32,
+            allowed_characters: vec![
+                CharacterClass::Letter,
+                CharacterClass::Digit,
+                CharacterClass::Underscore,
+            ],
+            reserved: [
+                "id",
+                "channel",
+                "username",
+                "follow",
+                "unfollow",
+                "ban",
+                "unban",
+                "is-follower",
+                "is-banned",
+                "followers",
+                "following",
+                "banned",
+                "banned-from",
+                "counts",
+                "relationship",
+                "batch",
+                "history",
+                "mute",
+                "unmute",
+                "is-muted",
+                "muted",
+                "muted-from",
+                "block",
+                "unblock",
+                "is-blocked",
+                "blocked",
+                "blocked-from",
+                "follow-requests",
+                "approve",
+                "reject",
+                "cancel",
+                "settings",
+                "add-moderator",
+                "remove-moderator",
+                "moderators",
+                "moderating",
+                "moderator",
+                "is-moderator",
+                "admin",
+                "ban-list",
+                "ban-lists",
+                "subscriptions",
+                "allow",
+                "disallow",
+                "allowed",
+                "add-member",
+                "remove-member",
+                "members",
+                "organizations",
+                "is-member",
+            ]
+            .map(String::from)
+            .to_vec(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AppState {
     pub db: Surreal<Client>,
@@ -36,8 +221,338 @@ pub struct AppState {
 pub struct Channel {
     pub id: Option<Thing>,
     pub username: String,
+    #[serde(default)]
+    pub normalized_username: String,
+    #[serde(default)]
+    pub username_skeleton: String,
+    #[serde(default)]
+    pub username_history: Vec<UsernameChange>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+    /// `None` for channels created before timestamps were recorded.
+    #[serde(default)]
+    pub created_at: Option<Datetime>,
+    #[serde(default)]
+    pub updated_at: Option<Datetime>,
     pub follower_list: Vec<Id>,
     pub banned_list: Vec<Id>,
     pub followed_list: Vec<Id>,
     pub banned_from_list: Vec<Id>,
+    #[serde(default)]
+    pub muted_list: Vec<Id>,
+    #[serde(default)]
+    pub muted_from_list: Vec<Id>,
+    #[serde(default)]
+    pub blocked_list: Vec<Id>,
+    #[serde(default)]
+    pub blocked_from_list: Vec<Id>,
+    /// Channels that can ban and mute on behalf of this channel.
+    #[serde(default)]
+    pub moderator_list: Vec<Id>,
+    #[serde(default)]
+    pub moderating_list: Vec<Id>,
+    /// Channels of this organization, they inherit its bans.
+    #[serde(default)]
+    pub member_list: Vec<Id>,
+    #[serde(default)]
+    pub organization_list: Vec<Id>,
+    /// Ban lists whose entries this channel treats as banned.
+    #[serde(default)]
+    pub ban_list_subscriptions: Vec<Thing>,
+    /// Channels not banned by the subscribed ban lists.
+    #[serde(default)]
+    pub allowed_list: Vec<Id>,
+    #[serde(default)]
+    pub settings: ChannelSettings,
+    #[serde(default)]
+    pub follower_count: usize,
+    #[serde(default)]
+    pub banned_count: usize,
+    #[serde(default)]
+    pub followed_count: usize,
+    #[serde(default)]
+    pub banned_from_count: usize,
+}
+
+/// Details of one follow, stored in the `follow` table under the pair of
+/// channel ids so they stay attached across username changes.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Follow {
+    pub id: Option<Thing>,
+    pub follower: Thing,
+    pub followed: Thing,
+    pub source: Option<String>,
+    pub created_at: Datetime,
+}
+
+/// Who can follow a channel.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AcceptFollows {
+    #[default]
+    Everyone,
+    /// Follows wait for the channel's approval.
+    Approval,
+    Nobody,
+}
+
+/// Relationship settings of a channel, stored with the channel.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChannelSettings {
+    #[serde(default)]
+    pub accept_follows: AcceptFollows,
+    /// Only the channel itself can list its followers.
+    #[serde(default)]
+    pub hide_follower_list: bool,
+    /// Only the channel itself can list the channels it follows.
+    #[serde(default)]
+    pub hide_following_list: bool,
+    /// Victims can see this channel in their banned from list and
+    /// relationship.
+    #[serde(default = "default_true")]
+    pub bans_visible_to_victim: bool,
+}
+
+impl Default for ChannelSettings {
+    fn default() -> Self {
+        ChannelSettings {
+            accept_follows: AcceptFollows::Everyone,
+            hide_follower_list: false,
+            hide_following_list: false,
+            bans_visible_to_victim: true,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Result of a follow, channels accepting follows on approval get a pending
+/// request instead.
+/// Both carry the follower's channel.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FollowOutcome {
+    Followed(Channel),
+    Requested(Channel),
+}
+
+impl FollowOutcome {
+    pub fn status(&self) -> &'static str {
+        match self {
+            FollowOutcome::Followed(_) => "followed",
+            FollowOutcome::Requested(_) => "requested",
+        }
+    }
+
+    pub fn channel(self) -> Channel {
+        match self {
+            FollowOutcome::Followed(channel) | FollowOutcome::Requested(channel) => channel,
+        }
+    }
+}
+
+/// What a ban keeps the victim from, a full ban covers chat too.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum BanScope {
+    /// Can still watch but can't chat.
+    Chat,
+    #[default]
+    Full,
+}
+
+impl BanScope {
+    pub fn covers(self, scope: BanScope) -> bool {
+        self >= scope
+    }
+}
+
+/// Details given with a ban.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BanDetails {
+    pub reason: Option<String>,
+    /// Identity of the moderator who issued the ban.
+    pub moderator: Option<String>,
+    pub notes: Option<String>,
+    /// Ban lifts itself after this many seconds, permanent when omitted.
+    pub duration_seconds: Option<u64>,
+    #[serde(default)]
+    pub scope: BanScope,
+}
+
+/// Details of one ban, stored in the `ban` table under the pair of channel
+/// ids.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Ban {
+    pub id: Option<Thing>,
+    pub victim: Thing,
+    pub judge: Thing,
+    pub reason: Option<String>,
+    pub moderator: Option<String>,
+    pub notes: Option<String>,
+    pub created_at: Datetime,
+    #[serde(default)]
+    pub expires_at: Option<Datetime>,
+    /// Bans recorded before scopes existed are full.
+    #[serde(default)]
+    pub scope: BanScope,
+}
+
+impl Ban {
+    /// Expired bans are inactive until the expiry task removes them.
+    pub fn is_expired(&self) -> bool {
+        match &self.expires_at {
+            Some(expires_at) => *expires_at <= Datetime::default(),
+            None => false,
+        }
+    }
+}
+
+/// Details of one mute, stored in the `mute` table under the pair of channel
+/// ids. A muted channel can still watch but can't chat.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Mute {
+    pub id: Option<Thing>,
+    pub victim: Thing,
+    pub judge: Thing,
+    /// Moderator who muted on behalf of the judge.
+    #[serde(default)]
+    pub moderator: Option<String>,
+    pub created_at: Datetime,
+    #[serde(default)]
+    pub expires_at: Option<Datetime>,
+}
+
+impl Mute {
+    pub fn is_expired(&self) -> bool {
+        match &self.expires_at {
+            Some(expires_at) => *expires_at <= Datetime::default(),
+            None => false,
+        }
+    }
+}
+
+/// Platform wide suspension of a channel, stored in the `global_ban` table
+/// under the channel id.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GlobalBan {
+    pub id: Option<Thing>,
+    pub channel: Thing,
+    pub reason: Option<String>,
+    pub created_at: Datetime,
+    #[serde(default)]
+    pub expires_at: Option<Datetime>,
+}
+
+impl GlobalBan {
+    pub fn is_expired(&self) -> bool {
+        match &self.expires_at {
+            Some(expires_at) => *expires_at <= Datetime::default(),
+            None => false,
+        }
+    }
+}
+
+/// Named ban list owned by a channel, stored in the `ban_list` table under the
+/// owner id and the lowercased name. Published lists can be subscribed to by
+/// other channels.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BanList {
+    pub id: Option<Thing>,
+    pub owner: Thing,
+    pub name: String,
+    #[serde(default)]
+    pub published: bool,
+    #[serde(default)]
+    pub entry_list: Vec<Id>,
+    #[serde(default)]
+    pub subscriber_list: Vec<Id>,
+    pub created_at: Datetime,
+}
+
+/// Number of one-time migrations applied, stored as `schema_version:current`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct SchemaVersion {
+    pub version: u32,
+}
+
+/// A username a channel used before `changed_at`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UsernameChange {
+    pub username: String,
+    pub normalized_username: String,
+    pub changed_at: Datetime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Relation {
+    Follower,
+    Followed,
+    Banned,
+    BannedFrom,
+    Muted,
+    MutedFrom,
+    Blocked,
+    BlockedFrom,
+    Moderator,
+    Moderating,
+    Member,
+    Organization,
+}
+
+impl Relation {
+    pub fn list(self, channel: &Channel) -> &Vec<Id> {
+        match self {
+            Relation::Follower => &channel.follower_list,
+            Relation::Followed => &channel.followed_list,
+            Relation::Banned => &channel.banned_list,
+            Relation::BannedFrom => &channel.banned_from_list,
+            Relation::Muted => &channel.muted_list,
+            Relation::MutedFrom => &channel.muted_from_list,
+            Relation::Blocked => &channel.blocked_list,
+            Relation::BlockedFrom => &channel.blocked_from_list,
+            Relation::Moderator => &channel.moderator_list,
+            Relation::Moderating => &channel.moderating_list,
+            Relation::Member => &channel.member_list,
+            Relation::Organization => &channel.organization_list,
+        }
+    }
+
+    pub fn list_mut(self, channel: &mut Channel) -> &mut Vec<Id> {
+        match self {
+            Relation::Follower => &mut channel.follower_list,
+            Relation::Followed => &mut channel.followed_list,
+            Relation::Banned => &mut channel.banned_list,
+            Relation::BannedFrom => &mut channel.banned_from_list,
+            Relation::Muted => &mut channel.muted_list,
+            Relation::MutedFrom => &mut channel.muted_from_list,
+            Relation::Blocked => &mut channel.blocked_list,
+            Relation::BlockedFrom => &mut channel.blocked_from_list,
+            Relation::Moderator => &mut channel.moderator_list,
+            Relation::Moderating => &mut channel.moderating_list,
+            Relation::Member => &mut channel.member_list,
+            Relation::Organization => &mut channel.organization_list,
+        }
+    }
+
+    /// Same relation seen from the other channel.
+    pub fn inverse(self) -> Relation {
+        match self {
+            Relation::Follower => Relation::Followed,
+            Relation::Followed => Relation::Follower,
+            Relation::Banned => Relation::BannedFrom,
+            Relation::BannedFrom => Relation::Banned,
+            Relation::Muted => Relation::MutedFrom,
+            Relation::MutedFrom => Relation::Muted,
+            Relation::Blocked => Relation::BlockedFrom,
+            Relation::BlockedFrom => Relation::Blocked,
+            Relation::Moderator => Relation::Moderating,
+            Relation::Moderating => Relation::Moderator,
+            Relation::Member => Relation::Organization,
+            Relation::Organization => Relation::Member,
+        }
+    }
 }
@@ -1,7 +1,9 @@
 use acapair_follow_ban_api::{
-    db::db_operations::connect,
+    db::db_operations::{
+        connect, migrate, remove_expired_bans, remove_expired_global_bans, remove_expired_mutes,
+    },
     routing,
-    utils::{database_config, server_config, tls_config},
+    utils::{api_config, database_config, server_config, tls_config},
     AppState,
 };
 use std::net::SocketAddr;
@@ -13,16 +15,44 @@ async fn main() {
     let database_config = database_config().await;
     let server_config = server_config().await;
     println!("{:#?}", database_config);
+    println!("{:#?}", api_config());
 
     let state = AppState {
         db: connect(&database_config).await.unwrap(),
     };
 
+    if let Err(err_val) = migrate(&state.db).await {
+        eprintln!("Error: Migrate | {}", err_val);
+        std::process::exit(1);
+    }
+
+    let db = state.db.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+            api_config().ban_expiry_interval_seconds,
+        ));
+        loop {
+            interval.tick().await;
+            let removed = remove_expired_bans(&db).await;
+            if removed > 0 {
+                println!("Expired Bans Removed: {}", removed);
+            }
+            let removed = remove_expired_mutes(&db).await;
+            if removed > 0 {
+                println!("Expired Mutes Removed: {}", removed);
+            }
+            let removed = remove_expired_global_bans(&db).await;
+            if removed > 0 {
+                println!("Expired Global Bans Removed: {}", removed);
+            }
+        }
+    });
+
     let app = routing::routing(axum::extract::State(state)).await;
     let addr = SocketAddr::new(server_config.ip_address, server_config.port);
     println!(
-        "\n\n\tOn Air -> https://{}\n\n",
-        format!("{}:{}", server_config.ip_address, server_config.port)
+        "\n\n\tOn Air -> https://{}:{}\n\n",
+        server_config.ip_address, server_config.port
     );
     axum_server::bind_rustls(addr, tls_config)
         .serve(app.into_make_service())
@@ -0,0 +1,489 @@
+use std::collections::BTreeMap;
+
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+    Json,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    error::{ApiError, ValidationError},
+    AcceptFollows, BanDetails, BanScope,
+};
+
+const MAX_DISPLAY_NAME_LENGTH: usize = 64;
+const MAX_METADATA_ENTRIES: usize = 32;
+const MAX_SOURCE_LENGTH: usize = 32;
+const MAX_REASON_LENGTH: usize = 256;
+const MAX_NOTES_LENGTH: usize = 1024;
+/// A hundred years, longer durations overflow the expiry date.
+const MAX_DURATION_SECONDS: u64 = 3_153_600_000;
+const MAX_BAN_LIST_NAME_LENGTH: usize = 64;
+
+/// JSON body extractor, rejects malformed bodies with 400 and invalid ones
+/// with 422 in the `ApiError` format.
+pub struct ValidJson<T>(pub T);
+
+pub trait Validate {
+    fn validate(&self) -> Result<(), ApiError>;
+}
+
+#[async_trait]
+impl<S, T> FromRequest<S> for ValidJson<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + Validate,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(body) = Json::<T>::from_request(request, state).await?;
+        body.validate()?;
+        Ok(ValidJson(body))
+    }
+}
+
+fn not_empty(field: &'static str, value: &str) -> Result<(), ApiError> {
+    match value.trim().is_empty() {
+        true => Err(ApiError::EmptyField(field)),
+        false => Ok(()),
+    }
+}
+
+fn at_most(
+    field: &'static str,
+    code: &'static str,
+    value: &str,
+    max_length: usize,
+) -> Result<(), ApiError> {
+    match value.chars().count() > max_length {
+        true => Err(ApiError::Validation(ValidationError {
+            field,
+            code,
+            message: format!("Too Long | At Most {} Characters", max_length),
+        })),
+        false => Ok(()),
+    }
+}
+
+/// Moderators are only recorded by "/moderator/ban", which checks them.
+fn no_moderator(details: &BanDetails) -> Result<(), ApiError> {
+    match details.moderator {
+        Some(_) => Err(ApiError::Validation(ValidationError {
+            field: "moderator",
+            code: "moderator_not_allowed",
+            message: "Moderator Bans Go Through /moderator/ban".to_string(),
+        })),
+        None => Ok(()),
+    }
+}
+
+fn valid_duration(duration_seconds: Option<u64>) -> Result<(), ApiError> {
+    match duration_seconds {
+        Some(0) => Err(ApiError::Validation(ValidationError {
+            field: "duration_seconds",
+            code: "duration_invalid",
+            message: "Duration Must Be Positive".to_string(),
+        })),
+        Some(duration_seconds) if duration_seconds > MAX_DURATION_SECONDS => {
+            Err(ApiError::Validation(ValidationError {
+                field: "duration_seconds",
+                code: "duration_invalid",
+                message: format!("Too Long | At Most {} Seconds", MAX_DURATION_SECONDS),
+            }))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BatchBody {
+    pub usernames: Vec<String>,
+}
+
+impl Validate for BatchBody {
+    fn validate(&self) -> Result<(), ApiError> {
+        for username in &self.usernames {
+            not_empty("usernames", username)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct UsernameBody {
+    pub username: String,
+}
+
+impl Validate for UsernameBody {
+    fn validate(&self) -> Result<(), ApiError> {
+        not_empty("username", &self.username)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ChangeUsernameBody {
+    pub username: String,
+    pub updated_username: String,
+}
+
+impl Validate for ChangeUsernameBody {
+    fn validate(&self) -> Result<(), ApiError> {
+        not_empty("username", &self.username)?;
+        not_empty("updated_username", &self.updated_username)
+    }
+}
+
+/// Omitted fields are left unchanged, an empty `display_name` clears it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ProfileBody {
+    pub username: String,
+    pub display_name: Option<String>,
+    pub metadata: Option<BTreeMap<String, String>>,
+}
+
+impl Validate for ProfileBody {
+    fn validate(&self) -> Result<(), ApiError> {
+        not_empty("username", &self.username)?;
+        if let Some(display_name) = &self.display_name {
+            if display_name.chars().count() > MAX_DISPLAY_NAME_LENGTH {
+                return Err(ApiError::Validation(ValidationError {
+                    field: "display_name",
+                    code: "display_name_too_long",
+                    message: format!(
+                        "Display Name Must Be At Most {} Characters",
+                        MAX_DISPLAY_NAME_LENGTH
+                    ),
+                }));
+            }
+        }
+        if let Some(metadata) = &self.metadata {
+            if metadata.len() > MAX_METADATA_ENTRIES {
+                return Err(ApiError::Validation(ValidationError {
+                    field: "metadata",
+                    code: "metadata_too_large",
+                    message: format!(
+                        "Metadata Must Have At Most {} Entries",
+                        MAX_METADATA_ENTRIES
+                    ),
+                }));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SettingsBody {
+    pub username: String,
+    pub accept_follows: Option<AcceptFollows>,
+    pub hide_follower_list: Option<bool>,
+    pub hide_following_list: Option<bool>,
+    pub bans_visible_to_victim: Option<bool>,
+}
+
+impl Validate for SettingsBody {
+    fn validate(&self) -> Result<(), ApiError> {
+        not_empty("username", &self.username)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct FollowBody {
+    pub follower: String,
+    pub followed: String,
+    /// Where the follow came from, e.g. `search`, `recommendation` or `raid`.
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+impl Validate for FollowBody {
+    fn validate(&self) -> Result<(), ApiError> {
+        not_empty("follower", &self.follower)?;
+        not_empty("followed", &self.followed)?;
+        if let Some(source) = &self.source {
+            not_empty("source", source)?;
+            at_most("source", "source_too_long", source, MAX_SOURCE_LENGTH)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct FollowRequestBody {
+    pub follower: String,
+    pub followed: String,
+}
+
+impl Validate for FollowRequestBody {
+    fn validate(&self) -> Result<(), ApiError> {
+        not_empty("follower", &self.follower)?;
+        not_empty("followed", &self.followed)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BanBody {
+    pub victim: String,
+    pub judge: String,
+    #[serde(flatten)]
+    pub details: BanDetails,
+}
+
+impl Validate for BanBody {
+    fn validate(&self) -> Result<(), ApiError> {
+        not_empty("victim", &self.victim)?;
+        not_empty("judge", &self.judge)?;
+        no_moderator(&self.details)?;
+        self.details.validate()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MuteBody {
+    pub victim: String,
+    pub judge: String,
+    /// Mute lifts itself after this many seconds, permanent when omitted.
+    #[serde(default)]
+    pub duration_seconds: Option<u64>,
+}
+
+impl Validate for MuteBody {
+    fn validate(&self) -> Result<(), ApiError> {
+        not_empty("victim", &self.victim)?;
+        not_empty("judge", &self.judge)?;
+        valid_duration(self.duration_seconds)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ModeratorBody {
+    pub moderator: String,
+    pub channel: String,
+}
+
+impl Validate for ModeratorBody {
+    fn validate(&self) -> Result<(), ApiError> {
+        not_empty("moderator", &self.moderator)?;
+        not_empty("channel", &self.channel)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MemberBody {
+    pub member: String,
+    pub organization: String,
+}
+
+impl Validate for MemberBody {
+    fn validate(&self) -> Result<(), ApiError> {
+        not_empty("member", &self.member)?;
+        not_empty("organization", &self.organization)
+    }
+}
+
+/// Ban issued by `moderator` on behalf of `judge`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ModeratorBanBody {
+    pub victim: String,
+    pub judge: String,
+    pub moderator: String,
+    pub reason: Option<String>,
+    pub notes: Option<String>,
+    pub duration_seconds: Option<u64>,
+    #[serde(default)]
+    pub scope: BanScope,
+}
+
+impl ModeratorBanBody {
+    pub fn details(&self) -> BanDetails {
+        BanDetails {
+            reason: self.reason.clone(),
+            moderator: Some(self.moderator.clone()),
+            notes: self.notes.clone(),
+            duration_seconds: self.duration_seconds,
+            scope: self.scope,
+        }
+    }
+}
+
+impl Validate for ModeratorBanBody {
+    fn validate(&self) -> Result<(), ApiError> {
+        not_empty("victim", &self.victim)?;
+        not_empty("judge", &self.judge)?;
+        self.details().validate()
+    }
+}
+
+/// Unban, mute or unmute by `moderator` on behalf of `judge`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ModeratorActionBody {
+    pub victim: String,
+    pub judge: String,
+    pub moderator: String,
+    /// Only used by mutes.
+    #[serde(default)]
+    pub duration_seconds: Option<u64>,
+}
+
+impl Validate for ModeratorActionBody {
+    fn validate(&self) -> Result<(), ApiError> {
+        not_empty("victim", &self.victim)?;
+        not_empty("judge", &self.judge)?;
+        not_empty("moderator", &self.moderator)?;
+        valid_duration(self.duration_seconds)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct GlobalBanBody {
+    pub username: String,
+    pub reason: Option<String>,
+    /// Global ban lifts itself after this many seconds, permanent when
+    /// omitted.
+    #[serde(default)]
+    pub duration_seconds: Option<u64>,
+}
+
+impl Validate for GlobalBanBody {
+    fn validate(&self) -> Result<(), ApiError> {
+        not_empty("username", &self.username)?;
+        if let Some(reason) = &self.reason {
+            not_empty("reason", reason)?;
+            at_most("reason", "reason_too_long", reason, MAX_REASON_LENGTH)?;
+        }
+        valid_duration(self.duration_seconds)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BanListBody {
+    pub owner: String,
+    pub name: String,
+}
+
+impl Validate for BanListBody {
+    fn validate(&self) -> Result<(), ApiError> {
+        not_empty("owner", &self.owner)?;
+        not_empty("name", &self.name)?;
+        at_most(
+            "name",
+            "ban_list_name_too_long",
+            &self.name,
+            MAX_BAN_LIST_NAME_LENGTH,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PublishBanListBody {
+    pub owner: String,
+    pub name: String,
+    pub published: bool,
+}
+
+impl Validate for PublishBanListBody {
+    fn validate(&self) -> Result<(), ApiError> {
+        not_empty("owner", &self.owner)?;
+        not_empty("name", &self.name)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BanListEntryBody {
+    pub owner: String,
+    pub name: String,
+    pub victim: String,
+}
+
+impl Validate for BanListEntryBody {
+    fn validate(&self) -> Result<(), ApiError> {
+        not_empty("owner", &self.owner)?;
+        not_empty("name", &self.name)?;
+        not_empty("victim", &self.victim)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SubscribeBody {
+    pub subscriber: String,
+    pub owner: String,
+    pub name: String,
+}
+
+impl Validate for SubscribeBody {
+    fn validate(&self) -> Result<(), ApiError> {
+        not_empty("subscriber", &self.subscriber)?;
+        not_empty("owner", &self.owner)?;
+        not_empty("name", &self.name)
+    }
+}
+
+/// Exempts `allowed` from the ban lists `judge` subscribes to.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AllowBody {
+    pub allowed: String,
+    pub judge: String,
+}
+
+impl Validate for AllowBody {
+    fn validate(&self) -> Result<(), ApiError> {
+        not_empty("allowed", &self.allowed)?;
+        not_empty("judge", &self.judge)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BlockBody {
+    pub blocker: String,
+    pub blocked: String,
+}
+
+impl Validate for BlockBody {
+    fn validate(&self) -> Result<(), ApiError> {
+        not_empty("blocker", &self.blocker)?;
+        not_empty("blocked", &self.blocked)
+    }
+}
+
+/// Batch ban, every ban is recorded with the same details.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BatchBanBody {
+    pub usernames: Vec<String>,
+    #[serde(flatten)]
+    pub details: BanDetails,
+}
+
+impl Validate for BatchBanBody {
+    fn validate(&self) -> Result<(), ApiError> {
+        for username in &self.usernames {
+            not_empty("usernames", username)?;
+        }
+        no_moderator(&self.details)?;
+        self.details.validate()
+    }
+}
+
+impl Validate for BanDetails {
+    fn validate(&self) -> Result<(), ApiError> {
+        if let Some(reason) = &self.reason {
+            not_empty("reason", reason)?;
+            at_most("reason", "reason_too_long", reason, MAX_REASON_LENGTH)?;
+        }
+        if let Some(moderator) = &self.moderator {
+            not_empty("moderator", moderator)?;
+        }
+        if let Some(notes) = &self.notes {
+            at_most("notes", "notes_too_long", notes, MAX_NOTES_LENGTH)?;
+        }
+        valid_duration(self.duration_seconds)
+    }
+}
+
+/// Scope asked of "/is-banned" and the batch ban checks, any ban counts when
+/// omitted.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ScopeQuery {
+    pub scope: Option<BanScope>,
+}
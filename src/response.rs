@@ -0,0 +1,181 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Id;
+
+use crate::{BanScope, Channel};
+
+/// Public representation of a channel.
+///
+/// Kept separate from the storage model so clients never see SurrealDB's
+/// `Thing`/`Id` encodings.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChannelResponse {
+    pub id: String,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub metadata: BTreeMap<String, String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub follower_count: usize,
+    pub followed_count: usize,
+    pub banned_count: usize,
+    pub banned_from_count: usize,
+}
+
+impl From<Channel> for ChannelResponse {
+    fn from(channel: Channel) -> Self {
+        ChannelResponse {
+            id: match channel.id {
+                Some(thing) => id_to_string(&thing.id),
+                None => String::new(),
+            },
+            username: channel.username,
+            display_name: channel.display_name,
+            metadata: channel.metadata,
+            created_at: channel.created_at.map(|created_at| created_at.to_raw()),
+            updated_at: channel.updated_at.map(|updated_at| updated_at.to_raw()),
+            follower_count: channel.follower_count,
+            followed_count: channel.followed_count,
+            banned_count: channel.banned_count,
+            banned_from_count: channel.banned_from_count,
+        }
+    }
+}
+
+/// Denormalized relation counters, readable without loading the lists.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ChannelCounts {
+    pub follower_count: usize,
+    pub followed_count: usize,
+    pub banned_count: usize,
+    pub banned_from_count: usize,
+}
+
+/// Relationship of a channel towards another one, seen from the first channel.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Relationship {
+    /// Channel follows the other channel.
+    pub follows: bool,
+    pub follows_since: Option<String>,
+    /// Other channel follows the channel.
+    pub followed_by: bool,
+    pub followed_by_since: Option<String>,
+    /// Channel has banned the other channel.
+    pub banned: bool,
+    /// Effective scope of the ban, inherited ones included.
+    pub banned_scope: Option<BanScope>,
+    pub banned_details: Option<BanInfo>,
+    /// Channel is banned by the other channel.
+    pub banned_by: bool,
+    pub banned_by_scope: Option<BanScope>,
+    pub banned_by_details: Option<BanInfo>,
+    /// Channel has muted the other channel.
+    pub muted: bool,
+    /// Channel is muted by the other channel.
+    pub muted_by: bool,
+    /// Channel has blocked the other channel.
+    pub blocked: bool,
+    /// Channel is blocked by the other channel.
+    pub blocked_by: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BanInfo {
+    pub since: String,
+    pub reason: Option<String>,
+    pub moderator: Option<String>,
+    pub notes: Option<String>,
+    pub expires_at: Option<String>,
+    pub scope: BanScope,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct GlobalBanEntry {
+    pub id: String,
+    pub username: String,
+    pub reason: Option<String>,
+    pub since: String,
+    pub expires_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BanListResponse {
+    pub owner: String,
+    pub name: String,
+    pub published: bool,
+    /// Usernames in the list.
+    pub entries: Vec<String>,
+    pub subscriber_count: usize,
+    pub created_at: String,
+}
+
+pub fn id_to_string(id: &Id) -> String {
+    id.to_raw()
+}
+
+/// Channel of a relation list, with when and how the relation was made.
+///
+/// `source` is set for follows, `reason`, `moderator` and `notes` for bans,
+/// `expires_at` for temporary bans and mutes.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RelationEntry {
+    pub id: String,
+    pub username: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub moderator: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    /// Organization the ban is inherited from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inherited_from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<BanScope>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ListSort {
+    /// Alphabetical by username.
+    #[default]
+    Name,
+    /// In the order the relations were created.
+    Time,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Query parameters of the list endpoints.
+///
+/// `cursor` is `next_cursor` of the previous page, the sort key and id of its
+/// last channel.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Pagination {
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub sort: ListSort,
+    #[serde(default)]
+    pub order: SortOrder,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Page<T> {
+    pub total: usize,
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+}
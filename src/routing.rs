@@ -1,13 +1,27 @@
+use std::collections::BTreeMap;
+
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::IntoResponse,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Redirect},
     routing::{delete, get, patch, post},
     Json, Router,
 };
 use tower_http::cors::CorsLayer;
 
-use crate::{db::db_operations, utils::database_config, AppState};
+use crate::{
+    db::db_operations,
+    error::ApiError,
+    request::{
+        AllowBody, BanBody, BanListBody, BanListEntryBody, BatchBanBody, BatchBody, BlockBody,
+        ChangeUsernameBody, FollowBody, FollowRequestBody, GlobalBanBody, MemberBody,
+        ModeratorActionBody, ModeratorBanBody, ModeratorBody, MuteBody, ProfileBody,
+        PublishBanListBody, ScopeQuery, SettingsBody, SubscribeBody, UsernameBody, ValidJson,
+    },
+    response::{ChannelResponse, Pagination},
+    utils::{api_config, database_config},
+    AppState, Relation,
+};
 
 pub async fn routing(State(state): State<AppState>) -> Router {
     Router::new()
@@ -16,6 +30,45 @@ pub async fn routing(State(state): State<AppState>) -> Router {
         .route("/:username", delete(delete_channel))
         .route("/:username", get(search_username))
         .route("/id/:id", get(search_id))
+        .route("/history/:username", get(username_history))
+        .route("/settings/:username", get(settings))
+        .route("/channel", post(create_channel_json))
+        .route("/channel", delete(delete_channel_json))
+        .route("/channel/username", patch(change_username_json))
+        .route("/channel/profile", patch(update_profile))
+        .route("/channel/settings", patch(update_settings))
+        .route("/follow", patch(follow_json))
+        .route("/unfollow", patch(unfollow_json))
+        .route("/ban", patch(ban_json))
+        .route("/unban", patch(unban_json))
+        .route("/mute", patch(mute_json))
+        .route("/unmute", patch(unmute_json))
+        .route("/block", patch(block_json))
+        .route("/unblock", patch(unblock_json))
+        .route("/approve", patch(approve_json))
+        .route("/reject", patch(reject_json))
+        .route("/cancel", patch(cancel_json))
+        .route("/add-moderator", patch(add_moderator_json))
+        .route("/remove-moderator", patch(remove_moderator_json))
+        .route("/add-member", patch(add_member_json))
+        .route("/remove-member", patch(remove_member_json))
+        .route("/moderator/ban", patch(moderator_ban))
+        .route("/moderator/unban", patch(moderator_unban))
+        .route("/moderator/mute", patch(moderator_mute))
+        .route("/moderator/unmute", patch(moderator_unmute))
+        .route("/admin/global-ban", patch(global_ban))
+        .route("/admin/global-unban", patch(global_unban))
+        .route("/admin/global-ban/:username", get(global_ban_details))
+        .route("/admin/global-bans", get(global_bans))
+        .route("/ban-list", post(create_ban_list))
+        .route("/ban-list", delete(delete_ban_list))
+        .route("/ban-list/publish", patch(publish_ban_list))
+        .route("/ban-list/add", patch(add_to_ban_list))
+        .route("/ban-list/remove", patch(remove_from_ban_list))
+        .route("/ban-list/subscribe", patch(subscribe_ban_list))
+        .route("/ban-list/unsubscribe", patch(unsubscribe_ban_list))
+        .route("/allow", patch(allow))
+        .route("/disallow", patch(disallow))
         .route(
             "/username/:username/:updated_username",
             patch(change_username),
@@ -24,8 +77,51 @@ pub async fn routing(State(state): State<AppState>) -> Router {
         .route("/unfollow/:follower/:followed", patch(unfollow))
         .route("/ban/:victim/:judge", patch(ban))
         .route("/unban/:victim/:judge", patch(unban))
+        .route("/mute/:victim/:judge", patch(mute))
+        .route("/unmute/:victim/:judge", patch(unmute))
+        .route("/block/:blocker/:blocked", patch(block))
+        .route("/unblock/:blocker/:blocked", patch(unblock))
+        .route("/approve/:follower/:followed", patch(approve))
+        .route("/reject/:follower/:followed", patch(reject))
+        .route("/cancel/:follower/:followed", patch(cancel))
+        .route("/add-moderator/:moderator/:channel", patch(add_moderator))
+        .route(
+            "/remove-moderator/:moderator/:channel",
+            patch(remove_moderator),
+        )
         .route("/is-follower/:follower/:followed", get(is_follower))
         .route("/is-banned/:victim/:judge", get(is_banned))
+        .route("/is-muted/:victim/:judge", get(is_muted))
+        .route("/is-blocked/:blocked/:blocker", get(is_blocked))
+        .route("/is-moderator/:moderator/:channel", get(is_moderator))
+        .route("/is-member/:member/:organization", get(is_member))
+        .route("/batch/is-follower/:followed", post(are_followers))
+        .route("/batch/is-following/:follower", post(is_following))
+        .route("/batch/is-banned/:judge", post(are_banned))
+        .route("/batch/is-banned-from/:victim", post(is_banned_from))
+        .route("/batch/follow/:follower", patch(batch_follow))
+        .route("/batch/unfollow/:follower", patch(batch_unfollow))
+        .route("/batch/ban/:judge", patch(batch_ban))
+        .route("/batch/unban/:judge", patch(batch_unban))
+        .route("/relationship/:username/:other", get(relationship))
+        .route("/counts/:username", get(counts))
+        .route("/followers/:username", get(followers))
+        .route("/following/:username", get(following))
+        .route("/banned/:username", get(banned))
+        .route("/banned-from/:username", get(banned_from))
+        .route("/muted/:username", get(muted))
+        .route("/muted-from/:username", get(muted_from))
+        .route("/blocked/:username", get(blocked))
+        .route("/blocked-from/:username", get(blocked_from))
+        .route("/follow-requests/:username", get(follow_requests))
+        .route("/moderators/:username", get(moderators))
+        .route("/moderating/:username", get(moderating))
+        .route("/members/:username", get(members))
+        .route("/organizations/:username", get(organizations))
+        .route("/ban-list/:owner/:name", get(ban_list))
+        .route("/ban-lists/:owner", get(ban_lists))
+        .route("/subscriptions/:username", get(ban_list_subscriptions))
+        .route("/allowed/:username", get(allowed))
         .layer(CorsLayer::permissive())
         .with_state(state.clone())
 }
@@ -49,7 +145,7 @@ async fn create_channel(
     match db_operations::create(&username, &state.db).await {
         Some(channel) => {
             let create = serde_json::json!({
-                "channel":channel,
+                "channel":ChannelResponse::from(channel),
             });
             (StatusCode::CREATED, Json(create))
         }
@@ -63,7 +159,7 @@ async fn delete_channel(
     match db_operations::delete(&username, &state.db).await {
         Some(channel) => {
             let delete = serde_json::json!({
-                "channel":channel,
+                "channel":ChannelResponse::from(channel),
             });
             (StatusCode::NO_CONTENT, Json(delete))
         }
@@ -74,21 +170,64 @@ async fn search_username(
     Path(username): Path<String>,
     State(state): State<AppState>,
 ) -> impl IntoResponse {
-    match db_operations::search_username(&username, &state.db).await {
-        Some(channel) => {
+    match db_operations::resolve_username(&username, &state.db).await {
+        Some((channel, true)) => {
+            Redirect::temporary(&format!("/{}", percent_encode(&channel.username))).into_response()
+        }
+        Some((channel, false)) => {
             let search_username = serde_json::json!({
-                "channel":channel,
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(search_username)).into_response()
+        }
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))).into_response(),
+    }
+}
+
+async fn username_history(
+    Path(username): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match db_operations::username_history(&username, &state.db).await {
+        Some(history) => {
+            let history: Vec<serde_json::Value> = history
+                .into_iter()
+                .map(|change| {
+                    serde_json::json!({
+                        "username":change.username,
+                        "changed_at":change.changed_at.to_raw(),
+                    })
+                })
+                .collect();
+            let username_history = serde_json::json!({
+                "history":history,
+            });
+            (StatusCode::OK, Json(username_history))
+        }
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
+    }
+}
+
+async fn settings(
+    Path(username): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match db_operations::settings(&username, &state.db).await {
+        Some(settings) => {
+            let settings = serde_json::json!({
+                "settings":settings,
             });
-            (StatusCode::OK, Json(search_username))
+            (StatusCode::OK, Json(settings))
         }
         None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
     }
 }
+
 async fn search_id(Path(id): Path<String>, State(state): State<AppState>) -> impl IntoResponse {
     match db_operations::search_id(&id, &state.db).await {
         Some(channel) => {
             let search_id = serde_json::json!({
-                "channel":channel,
+                "channel":ChannelResponse::from(channel),
             });
             (StatusCode::OK, Json(search_id))
         }
@@ -102,7 +241,7 @@ async fn change_username(
     match db_operations::change_username(&updated_username, &username, &state.db).await {
         Some(channel) => {
             let change_username = serde_json::json!({
-                "channel":channel,
+                "channel":ChannelResponse::from(channel),
             });
             (StatusCode::OK, Json(change_username))
         }
@@ -113,10 +252,14 @@ async fn follow(
     Path((follower, followed)): Path<(String, String)>,
     State(state): State<AppState>,
 ) -> impl IntoResponse {
-    match db_operations::follow(&follower, &followed, &state.db).await {
-        Some(channel) => {
+    match db_operations::try_follow(&follower, &followed, None, &state.db)
+        .await
+        .ok()
+    {
+        Some(outcome) => {
             let follow = serde_json::json!({
-                "channel":channel,
+                "status":outcome.status(),
+                "channel":ChannelResponse::from(outcome.channel()),
             });
             (StatusCode::OK, Json(follow))
         }
@@ -130,7 +273,7 @@ async fn unfollow(
     match db_operations::unfollow(&follower, &followed, &state.db).await {
         Some(channel) => {
             let unfollow = serde_json::json!({
-                "channel":channel,
+                "channel":ChannelResponse::from(channel),
             });
             (StatusCode::OK, Json(unfollow))
         }
@@ -144,7 +287,7 @@ async fn ban(
     match db_operations::ban(&victim, &judge, &state.db).await {
         Some(channel) => {
             let ban = serde_json::json!({
-                "channel":channel,
+                "channel":ChannelResponse::from(channel),
             });
             (StatusCode::OK, Json(ban))
         }
@@ -158,52 +301,1296 @@ async fn unban(
     match db_operations::unban(&victim, &judge, &state.db).await {
         Some(channel) => {
             let unban = serde_json::json!({
-                "channel":channel,
+                "channel":ChannelResponse::from(channel),
             });
             (StatusCode::OK, Json(unban))
         }
         None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
     }
 }
-
-async fn is_follower(
+async fn mute(
+    Path((victim, judge)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match db_operations::mute(&victim, &judge, &state.db).await {
+        Some(channel) => {
+            let mute = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(mute))
+        }
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
+    }
+}
+async fn unmute(
+    Path((victim, judge)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match db_operations::unmute(&victim, &judge, &state.db).await {
+        Some(channel) => {
+            let unmute = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(unmute))
+        }
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
+    }
+}
+async fn block(
+    Path((blocker, blocked)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match db_operations::block(&blocker, &blocked, &state.db).await {
+        Some(channel) => {
+            let block = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(block))
+        }
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
+    }
+}
+async fn unblock(
+    Path((blocker, blocked)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match db_operations::unblock(&blocker, &blocked, &state.db).await {
+        Some(channel) => {
+            let unblock = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(unblock))
+        }
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
+    }
+}
+async fn approve(
     Path((follower, followed)): Path<(String, String)>,
     State(state): State<AppState>,
 ) -> impl IntoResponse {
-    let is_follower = db_operations::is_follower(&follower, &followed, &state.db).await;
-    match is_follower {
-        true => {
-            let is_follower = serde_json::json!({
-                "is_follower":true
+    match db_operations::approve_follow_request(&follower, &followed, &state.db).await {
+        Some(channel) => {
+            let approve = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
             });
-            (StatusCode::OK, Json(is_follower))
+            (StatusCode::OK, Json(approve))
         }
-        false => {
-            let is_follower = serde_json::json!({
-                "is_follower":false
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
+    }
+}
+async fn reject(
+    Path((follower, followed)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match db_operations::reject_follow_request(&follower, &followed, &state.db).await {
+        Some(channel) => {
+            let reject = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
             });
-            (StatusCode::OK, Json(is_follower))
+            (StatusCode::OK, Json(reject))
+        }
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
+    }
+}
+async fn cancel(
+    Path((follower, followed)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match db_operations::cancel_follow_request(&follower, &followed, &state.db).await {
+        Some(channel) => {
+            let cancel = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(cancel))
+        }
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
+    }
+}
+async fn add_moderator(
+    Path((moderator, channel)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match db_operations::add_moderator(&moderator, &channel, &state.db).await {
+        Some(channel) => {
+            let add_moderator = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(add_moderator))
         }
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
+    }
+}
+async fn remove_moderator(
+    Path((moderator, channel)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match db_operations::remove_moderator(&moderator, &channel, &state.db).await {
+        Some(channel) => {
+            let remove_moderator = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(remove_moderator))
+        }
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
     }
 }
 
-async fn is_banned(
-    Path((victim, judge)): Path<(String, String)>,
+async fn create_channel_json(
     State(state): State<AppState>,
+    ValidJson(body): ValidJson<UsernameBody>,
 ) -> impl IntoResponse {
-    let is_banned = db_operations::is_banned(&victim, &judge, &state.db).await;
-    match is_banned {
-        true => {
-            let is_banned = serde_json::json!({
-                "is_banned":true
+    match db_operations::try_create(&body.username, &state.db).await {
+        Ok(channel) => {
+            let create = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
             });
-            (StatusCode::OK, Json(is_banned))
+            (StatusCode::CREATED, Json(create)).into_response()
         }
-        false => {
-            let is_banned = serde_json::json!({
-                "is_banned":false
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn delete_channel_json(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<UsernameBody>,
+) -> impl IntoResponse {
+    match db_operations::try_delete(&body.username, &state.db).await {
+        Ok(channel) => {
+            let delete = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(delete)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn change_username_json(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<ChangeUsernameBody>,
+) -> impl IntoResponse {
+    match db_operations::try_change_username(&body.updated_username, &body.username, &state.db)
+        .await
+    {
+        Ok(channel) => {
+            let change_username = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(change_username)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn update_profile(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<ProfileBody>,
+) -> impl IntoResponse {
+    match db_operations::update_profile(&body.username, body.display_name, body.metadata, &state.db)
+        .await
+    {
+        Ok(channel) => {
+            let update_profile = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(update_profile)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn update_settings(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<SettingsBody>,
+) -> impl IntoResponse {
+    match db_operations::update_settings(
+        &body.username,
+        body.accept_follows,
+        body.hide_follower_list,
+        body.hide_following_list,
+        body.bans_visible_to_victim,
+        &state.db,
+    )
+    .await
+    {
+        Ok(channel) => {
+            let update_settings = serde_json::json!({
+                "settings":channel.settings,
+            });
+            (StatusCode::OK, Json(update_settings)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn follow_json(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<FollowBody>,
+) -> impl IntoResponse {
+    match db_operations::try_follow(&body.follower, &body.followed, body.source, &state.db).await {
+        Ok(outcome) => {
+            let follow = serde_json::json!({
+                "status":outcome.status(),
+                "channel":ChannelResponse::from(outcome.channel()),
+            });
+            (StatusCode::OK, Json(follow)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn unfollow_json(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<FollowBody>,
+) -> impl IntoResponse {
+    match db_operations::try_unfollow(&body.follower, &body.followed, &state.db).await {
+        Ok(channel) => {
+            let unfollow = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(unfollow)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn ban_json(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<BanBody>,
+) -> impl IntoResponse {
+    match db_operations::try_ban(&body.victim, &body.judge, body.details, &state.db).await {
+        Ok(channel) => {
+            let ban = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(ban)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn unban_json(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<BanBody>,
+) -> impl IntoResponse {
+    match db_operations::try_unban(&body.victim, &body.judge, &state.db).await {
+        Ok(channel) => {
+            let unban = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(unban)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn mute_json(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<MuteBody>,
+) -> impl IntoResponse {
+    match db_operations::try_mute(&body.victim, &body.judge, body.duration_seconds, &state.db).await
+    {
+        Ok(channel) => {
+            let mute = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(mute)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn unmute_json(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<MuteBody>,
+) -> impl IntoResponse {
+    match db_operations::try_unmute(&body.victim, &body.judge, &state.db).await {
+        Ok(channel) => {
+            let unmute = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(unmute)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn block_json(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<BlockBody>,
+) -> impl IntoResponse {
+    match db_operations::try_block(&body.blocker, &body.blocked, &state.db).await {
+        Ok(channel) => {
+            let block = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(block)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn unblock_json(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<BlockBody>,
+) -> impl IntoResponse {
+    match db_operations::try_unblock(&body.blocker, &body.blocked, &state.db).await {
+        Ok(channel) => {
+            let unblock = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(unblock)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn approve_json(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<FollowRequestBody>,
+) -> impl IntoResponse {
+    match db_operations::try_approve_follow_request(&body.follower, &body.followed, &state.db).await
+    {
+        Ok(channel) => {
+            let approve = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(approve)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn reject_json(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<FollowRequestBody>,
+) -> impl IntoResponse {
+    match db_operations::try_remove_follow_request(&body.follower, &body.followed, &state.db).await
+    {
+        Ok(channel) => {
+            let reject = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(reject)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn cancel_json(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<FollowRequestBody>,
+) -> impl IntoResponse {
+    match db_operations::try_remove_follow_request(&body.follower, &body.followed, &state.db).await
+    {
+        Ok(channel) => {
+            let cancel = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(cancel)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn add_moderator_json(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<ModeratorBody>,
+) -> impl IntoResponse {
+    match db_operations::try_add_moderator(&body.moderator, &body.channel, &state.db).await {
+        Ok(channel) => {
+            let add_moderator = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(add_moderator)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn add_member_json(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<MemberBody>,
+) -> impl IntoResponse {
+    match db_operations::try_add_member(&body.member, &body.organization, &state.db).await {
+        Ok(channel) => {
+            let add_member = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(add_member)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn remove_member_json(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<MemberBody>,
+) -> impl IntoResponse {
+    match db_operations::try_remove_member(&body.member, &body.organization, &state.db).await {
+        Ok(channel) => {
+            let remove_member = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
             });
-            (StatusCode::OK, Json(is_banned))
+            (StatusCode::OK, Json(remove_member)).into_response()
         }
+        Err(err_val) => err_val.into_response(),
     }
 }
+
+async fn remove_moderator_json(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<ModeratorBody>,
+) -> impl IntoResponse {
+    match db_operations::try_remove_moderator(&body.moderator, &body.channel, &state.db).await {
+        Ok(channel) => {
+            let remove_moderator = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(remove_moderator)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn moderator_ban(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<ModeratorBanBody>,
+) -> impl IntoResponse {
+    match db_operations::try_moderator_ban(
+        &body.victim,
+        &body.judge,
+        &body.moderator,
+        body.details(),
+        &state.db,
+    )
+    .await
+    {
+        Ok(channel) => {
+            let ban = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(ban)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn moderator_unban(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<ModeratorActionBody>,
+) -> impl IntoResponse {
+    match db_operations::try_moderator_unban(&body.victim, &body.judge, &body.moderator, &state.db)
+        .await
+    {
+        Ok(channel) => {
+            let unban = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(unban)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn moderator_mute(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<ModeratorActionBody>,
+) -> impl IntoResponse {
+    match db_operations::try_moderator_mute(
+        &body.victim,
+        &body.judge,
+        &body.moderator,
+        body.duration_seconds,
+        &state.db,
+    )
+    .await
+    {
+        Ok(channel) => {
+            let mute = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(mute)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn moderator_unmute(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<ModeratorActionBody>,
+) -> impl IntoResponse {
+    match db_operations::try_moderator_unmute(&body.victim, &body.judge, &body.moderator, &state.db)
+        .await
+    {
+        Ok(channel) => {
+            let unmute = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(unmute)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn global_ban(
+    headers: HeaderMap,
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<GlobalBanBody>,
+) -> impl IntoResponse {
+    if let Err(err_val) = check_admin(&headers) {
+        return err_val.into_response();
+    }
+    match db_operations::try_global_ban(
+        &body.username,
+        body.reason,
+        body.duration_seconds,
+        &state.db,
+    )
+    .await
+    {
+        Ok(_) => match db_operations::global_ban_details(&body.username, &state.db).await {
+            Some(global_ban) => {
+                let global_ban = serde_json::json!({
+                    "global_ban":global_ban,
+                });
+                (StatusCode::OK, Json(global_ban)).into_response()
+            }
+            None => ApiError::Database.into_response(),
+        },
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn global_unban(
+    headers: HeaderMap,
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<UsernameBody>,
+) -> impl IntoResponse {
+    if let Err(err_val) = check_admin(&headers) {
+        return err_val.into_response();
+    }
+    match db_operations::try_global_unban(&body.username, &state.db).await {
+        Ok(_) => {
+            let global_unban = serde_json::json!({
+                "username":body.username,
+            });
+            (StatusCode::OK, Json(global_unban)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn global_ban_details(
+    headers: HeaderMap,
+    Path(username): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    if let Err(err_val) = check_admin(&headers) {
+        return err_val.into_response();
+    }
+    match db_operations::global_ban_details(&username, &state.db).await {
+        Some(global_ban) => {
+            let global_ban = serde_json::json!({
+                "global_ban":global_ban,
+            });
+            (StatusCode::OK, Json(global_ban)).into_response()
+        }
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))).into_response(),
+    }
+}
+
+async fn global_bans(headers: HeaderMap, State(state): State<AppState>) -> impl IntoResponse {
+    if let Err(err_val) = check_admin(&headers) {
+        return err_val.into_response();
+    }
+    let global_bans = serde_json::json!({
+        "global_bans":db_operations::list_global_bans(&state.db).await,
+    });
+    (StatusCode::OK, Json(global_bans)).into_response()
+}
+
+async fn create_ban_list(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<BanListBody>,
+) -> impl IntoResponse {
+    match db_operations::try_create_ban_list(&body.owner, &body.name, &state.db).await {
+        Ok(ban_list) => {
+            let ban_list = serde_json::json!({
+                "ban_list":ban_list,
+            });
+            (StatusCode::CREATED, Json(ban_list)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn delete_ban_list(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<BanListBody>,
+) -> impl IntoResponse {
+    match db_operations::try_delete_ban_list(&body.owner, &body.name, &state.db).await {
+        Ok(ban_list) => {
+            let ban_list = serde_json::json!({
+                "ban_list":ban_list,
+            });
+            (StatusCode::OK, Json(ban_list)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn publish_ban_list(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<PublishBanListBody>,
+) -> impl IntoResponse {
+    match db_operations::try_publish_ban_list(&body.owner, &body.name, body.published, &state.db)
+        .await
+    {
+        Ok(ban_list) => {
+            let ban_list = serde_json::json!({
+                "ban_list":ban_list,
+            });
+            (StatusCode::OK, Json(ban_list)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn add_to_ban_list(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<BanListEntryBody>,
+) -> impl IntoResponse {
+    match db_operations::try_add_to_ban_list(&body.owner, &body.name, &body.victim, &state.db).await
+    {
+        Ok(ban_list) => {
+            let ban_list = serde_json::json!({
+                "ban_list":ban_list,
+            });
+            (StatusCode::OK, Json(ban_list)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn remove_from_ban_list(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<BanListEntryBody>,
+) -> impl IntoResponse {
+    match db_operations::try_remove_from_ban_list(&body.owner, &body.name, &body.victim, &state.db)
+        .await
+    {
+        Ok(ban_list) => {
+            let ban_list = serde_json::json!({
+                "ban_list":ban_list,
+            });
+            (StatusCode::OK, Json(ban_list)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn subscribe_ban_list(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<SubscribeBody>,
+) -> impl IntoResponse {
+    match db_operations::try_subscribe_ban_list(
+        &body.subscriber,
+        &body.owner,
+        &body.name,
+        &state.db,
+    )
+    .await
+    {
+        Ok(channel) => {
+            let subscribe = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(subscribe)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn unsubscribe_ban_list(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<SubscribeBody>,
+) -> impl IntoResponse {
+    match db_operations::try_unsubscribe_ban_list(
+        &body.subscriber,
+        &body.owner,
+        &body.name,
+        &state.db,
+    )
+    .await
+    {
+        Ok(channel) => {
+            let unsubscribe = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(unsubscribe)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn allow(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<AllowBody>,
+) -> impl IntoResponse {
+    match db_operations::try_allow(&body.allowed, &body.judge, &state.db).await {
+        Ok(channel) => {
+            let allow = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(allow)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn disallow(
+    State(state): State<AppState>,
+    ValidJson(body): ValidJson<AllowBody>,
+) -> impl IntoResponse {
+    match db_operations::try_disallow(&body.allowed, &body.judge, &state.db).await {
+        Ok(channel) => {
+            let disallow = serde_json::json!({
+                "channel":ChannelResponse::from(channel),
+            });
+            (StatusCode::OK, Json(disallow)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn ban_list(
+    Path((owner, name)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match db_operations::ban_list(&owner, &name, &state.db).await {
+        Some(ban_list) => {
+            let ban_list = serde_json::json!({
+                "ban_list":ban_list,
+            });
+            (StatusCode::OK, Json(ban_list))
+        }
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
+    }
+}
+
+async fn ban_lists(Path(owner): Path<String>, State(state): State<AppState>) -> impl IntoResponse {
+    match db_operations::ban_lists(&owner, &state.db).await {
+        Some(ban_lists) => {
+            let ban_lists = serde_json::json!({
+                "ban_lists":ban_lists,
+            });
+            (StatusCode::OK, Json(ban_lists))
+        }
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
+    }
+}
+
+async fn ban_list_subscriptions(
+    Path(username): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match db_operations::ban_list_subscriptions(&username, &state.db).await {
+        Some(ban_lists) => {
+            let subscriptions = serde_json::json!({
+                "subscriptions":ban_lists,
+            });
+            (StatusCode::OK, Json(subscriptions))
+        }
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
+    }
+}
+
+async fn allowed(Path(username): Path<String>, State(state): State<AppState>) -> impl IntoResponse {
+    match db_operations::list_allowed(&username, &state.db).await {
+        Some(allowed) => {
+            let allowed = serde_json::json!({
+                "allowed":allowed,
+            });
+            (StatusCode::OK, Json(allowed))
+        }
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
+    }
+}
+
+/// Admin URLs need `admin_token` in the `x-admin-token` header.
+fn is_admin(headers: &HeaderMap) -> bool {
+    let admin_token = &api_config().admin_token;
+    headers
+        .get("x-admin-token")
+        .and_then(|token| token.to_str().ok())
+        .is_some_and(|token| !admin_token.is_empty() && token == admin_token)
+}
+
+fn check_admin(headers: &HeaderMap) -> Result<(), ApiError> {
+    match is_admin(headers) {
+        true => Ok(()),
+        false => {
+            eprintln!("Error: Admin | Unauthorized");
+            Err(ApiError::Unauthorized)
+        }
+    }
+}
+
+async fn is_follower(
+    Path((follower, followed)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let is_follower = db_operations::is_follower(&follower, &followed, &state.db).await;
+    match is_follower {
+        true => {
+            let is_follower = serde_json::json!({
+                "is_follower":true
+            });
+            (StatusCode::OK, Json(is_follower))
+        }
+        false => {
+            let is_follower = serde_json::json!({
+                "is_follower":false
+            });
+            (StatusCode::OK, Json(is_follower))
+        }
+    }
+}
+
+async fn is_banned(
+    Path((victim, judge)): Path<(String, String)>,
+    Query(query): Query<ScopeQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let scope = db_operations::is_banned(&victim, &judge, query.scope, &state.db).await;
+    let is_banned = serde_json::json!({
+        "is_banned":scope.is_some(),
+        "scope":scope,
+    });
+    (StatusCode::OK, Json(is_banned))
+}
+
+async fn is_muted(
+    Path((victim, judge)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let is_muted = db_operations::is_muted(&victim, &judge, &state.db).await;
+    let is_muted = serde_json::json!({
+        "is_muted":is_muted
+    });
+    (StatusCode::OK, Json(is_muted))
+}
+
+async fn is_blocked(
+    Path((blocked, blocker)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let is_blocked = db_operations::is_blocked(&blocked, &blocker, &state.db).await;
+    let is_blocked = serde_json::json!({
+        "is_blocked":is_blocked
+    });
+    (StatusCode::OK, Json(is_blocked))
+}
+
+async fn is_moderator(
+    Path((moderator, channel)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let is_moderator = db_operations::is_moderator(&moderator, &channel, &state.db).await;
+    let is_moderator = serde_json::json!({
+        "is_moderator":is_moderator
+    });
+    (StatusCode::OK, Json(is_moderator))
+}
+
+async fn is_member(
+    Path((member, organization)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let is_member = db_operations::is_member(&member, &organization, &state.db).await;
+    let is_member = serde_json::json!({
+        "is_member":is_member
+    });
+    (StatusCode::OK, Json(is_member))
+}
+
+async fn are_followers(
+    Path(followed): Path<String>,
+    State(state): State<AppState>,
+    ValidJson(batch): ValidJson<BatchBody>,
+) -> impl IntoResponse {
+    if batch.usernames.len() > api_config().max_batch_size {
+        return (StatusCode::PAYLOAD_TOO_LARGE, Json(serde_json::json!("")));
+    }
+    match db_operations::are_followers(&batch.usernames, &followed, &state.db).await {
+        Some(results) => {
+            let are_followers = serde_json::json!({
+                "is_follower":results,
+            });
+            (StatusCode::OK, Json(are_followers))
+        }
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
+    }
+}
+
+async fn is_following(
+    Path(follower): Path<String>,
+    State(state): State<AppState>,
+    ValidJson(batch): ValidJson<BatchBody>,
+) -> impl IntoResponse {
+    if batch.usernames.len() > api_config().max_batch_size {
+        return (StatusCode::PAYLOAD_TOO_LARGE, Json(serde_json::json!("")));
+    }
+    match db_operations::is_following(&follower, &batch.usernames, &state.db).await {
+        Some(results) => {
+            let is_following = serde_json::json!({
+                "is_following":results,
+            });
+            (StatusCode::OK, Json(is_following))
+        }
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
+    }
+}
+
+async fn are_banned(
+    Path(judge): Path<String>,
+    Query(query): Query<ScopeQuery>,
+    State(state): State<AppState>,
+    ValidJson(batch): ValidJson<BatchBody>,
+) -> impl IntoResponse {
+    if batch.usernames.len() > api_config().max_batch_size {
+        return (StatusCode::PAYLOAD_TOO_LARGE, Json(serde_json::json!("")));
+    }
+    match db_operations::are_banned(&batch.usernames, &judge, query.scope, &state.db).await {
+        Some(results) => {
+            let are_banned = serde_json::json!({
+                "is_banned":results
+                    .iter()
+                    .map(|(username, scope)| (username, scope.is_some()))
+                    .collect::<BTreeMap<_, _>>(),
+                "scope":results,
+            });
+            (StatusCode::OK, Json(are_banned))
+        }
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
+    }
+}
+
+async fn is_banned_from(
+    Path(victim): Path<String>,
+    Query(query): Query<ScopeQuery>,
+    State(state): State<AppState>,
+    ValidJson(batch): ValidJson<BatchBody>,
+) -> impl IntoResponse {
+    if batch.usernames.len() > api_config().max_batch_size {
+        return (StatusCode::PAYLOAD_TOO_LARGE, Json(serde_json::json!("")));
+    }
+    match db_operations::is_banned_from(&victim, &batch.usernames, query.scope, &state.db).await {
+        Some(results) => {
+            let is_banned_from = serde_json::json!({
+                "is_banned":results
+                    .iter()
+                    .map(|(username, scope)| (username, scope.is_some()))
+                    .collect::<BTreeMap<_, _>>(),
+                "scope":results,
+            });
+            (StatusCode::OK, Json(is_banned_from))
+        }
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
+    }
+}
+
+async fn batch_follow(
+    Path(follower): Path<String>,
+    State(state): State<AppState>,
+    ValidJson(batch): ValidJson<BatchBody>,
+) -> impl IntoResponse {
+    if batch.usernames.len() > api_config().max_batch_size {
+        return (StatusCode::PAYLOAD_TOO_LARGE, Json(serde_json::json!(""))).into_response();
+    }
+    match db_operations::batch_follow(&follower, &batch.usernames, &state.db).await {
+        Ok(results) => {
+            let batch_follow = serde_json::json!({
+                "results":results,
+            });
+            (StatusCode::OK, Json(batch_follow)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn batch_unfollow(
+    Path(follower): Path<String>,
+    State(state): State<AppState>,
+    ValidJson(batch): ValidJson<BatchBody>,
+) -> impl IntoResponse {
+    if batch.usernames.len() > api_config().max_batch_size {
+        return (StatusCode::PAYLOAD_TOO_LARGE, Json(serde_json::json!(""))).into_response();
+    }
+    match db_operations::batch_unfollow(&follower, &batch.usernames, &state.db).await {
+        Ok(results) => {
+            let batch_unfollow = serde_json::json!({
+                "results":results,
+            });
+            (StatusCode::OK, Json(batch_unfollow)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn batch_ban(
+    Path(judge): Path<String>,
+    State(state): State<AppState>,
+    ValidJson(batch): ValidJson<BatchBanBody>,
+) -> impl IntoResponse {
+    if batch.usernames.len() > api_config().max_batch_size {
+        return (StatusCode::PAYLOAD_TOO_LARGE, Json(serde_json::json!(""))).into_response();
+    }
+    match db_operations::batch_ban(&batch.usernames, &judge, batch.details, &state.db).await {
+        Ok(results) => {
+            let batch_ban = serde_json::json!({
+                "results":results,
+            });
+            (StatusCode::OK, Json(batch_ban)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn batch_unban(
+    Path(judge): Path<String>,
+    State(state): State<AppState>,
+    ValidJson(batch): ValidJson<BatchBody>,
+) -> impl IntoResponse {
+    if batch.usernames.len() > api_config().max_batch_size {
+        return (StatusCode::PAYLOAD_TOO_LARGE, Json(serde_json::json!(""))).into_response();
+    }
+    match db_operations::batch_unban(&batch.usernames, &judge, &state.db).await {
+        Ok(results) => {
+            let batch_unban = serde_json::json!({
+                "results":results,
+            });
+            (StatusCode::OK, Json(batch_unban)).into_response()
+        }
+        Err(err_val) => err_val.into_response(),
+    }
+}
+
+async fn relationship(
+    Path((username, other)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match db_operations::relationship(&username, &other, &state.db).await {
+        Some(relationship) => {
+            let relationship = serde_json::json!({
+                "relationship":relationship,
+            });
+            (StatusCode::OK, Json(relationship))
+        }
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
+    }
+}
+
+async fn counts(Path(username): Path<String>, State(state): State<AppState>) -> impl IntoResponse {
+    match db_operations::counts(&username, &state.db).await {
+        Some(counts) => {
+            let counts = serde_json::json!({
+                "counts":counts,
+            });
+            (StatusCode::OK, Json(counts))
+        }
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
+    }
+}
+
+async fn followers(
+    Path(username): Path<String>,
+    Query(pagination): Query<Pagination>,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    relation_list(&username, Relation::Follower, &pagination, &headers, &state).await
+}
+
+async fn following(
+    Path(username): Path<String>,
+    Query(pagination): Query<Pagination>,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    relation_list(&username, Relation::Followed, &pagination, &headers, &state).await
+}
+
+async fn banned(
+    Path(username): Path<String>,
+    Query(pagination): Query<Pagination>,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    relation_list(&username, Relation::Banned, &pagination, &headers, &state).await
+}
+
+async fn banned_from(
+    Path(username): Path<String>,
+    Query(pagination): Query<Pagination>,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    relation_list(
+        &username,
+        Relation::BannedFrom,
+        &pagination,
+        &headers,
+        &state,
+    )
+    .await
+}
+
+async fn muted(
+    Path(username): Path<String>,
+    Query(pagination): Query<Pagination>,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    relation_list(&username, Relation::Muted, &pagination, &headers, &state).await
+}
+
+async fn muted_from(
+    Path(username): Path<String>,
+    Query(pagination): Query<Pagination>,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    relation_list(
+        &username,
+        Relation::MutedFrom,
+        &pagination,
+        &headers,
+        &state,
+    )
+    .await
+}
+
+async fn blocked(
+    Path(username): Path<String>,
+    Query(pagination): Query<Pagination>,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    relation_list(&username, Relation::Blocked, &pagination, &headers, &state).await
+}
+
+async fn blocked_from(
+    Path(username): Path<String>,
+    Query(pagination): Query<Pagination>,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    relation_list(
+        &username,
+        Relation::BlockedFrom,
+        &pagination,
+        &headers,
+        &state,
+    )
+    .await
+}
+
+async fn moderators(
+    Path(username): Path<String>,
+    Query(pagination): Query<Pagination>,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    relation_list(
+        &username,
+        Relation::Moderator,
+        &pagination,
+        &headers,
+        &state,
+    )
+    .await
+}
+
+async fn moderating(
+    Path(username): Path<String>,
+    Query(pagination): Query<Pagination>,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    relation_list(
+        &username,
+        Relation::Moderating,
+        &pagination,
+        &headers,
+        &state,
+    )
+    .await
+}
+
+async fn members(
+    Path(username): Path<String>,
+    Query(pagination): Query<Pagination>,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    relation_list(&username, Relation::Member, &pagination, &headers, &state).await
+}
+
+async fn organizations(
+    Path(username): Path<String>,
+    Query(pagination): Query<Pagination>,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    relation_list(
+        &username,
+        Relation::Organization,
+        &pagination,
+        &headers,
+        &state,
+    )
+    .await
+}
+
+async fn follow_requests(
+    Path(username): Path<String>,
+    Query(pagination): Query<Pagination>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match db_operations::list_follow_requests(&username, &pagination, &state.db).await {
+        Some(page) => (StatusCode::OK, Json(serde_json::json!(page))),
+        None => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))),
+    }
+}
+
+async fn relation_list(
+    username: &String,
+    relation: Relation,
+    pagination: &Pagination,
+    headers: &HeaderMap,
+    state: &AppState,
+) -> impl IntoResponse {
+    // Requests can't prove which channel is viewing, so hidden lists are only
+    // listed to admins.
+    let viewer = is_admin(headers).then_some(username);
+    match db_operations::try_list_relation(username, relation, pagination, viewer, &state.db).await
+    {
+        Ok(page) => (StatusCode::OK, Json(serde_json::json!(page))).into_response(),
+        Err(ApiError::ListHidden) => ApiError::ListHidden.into_response(),
+        Err(_) => (StatusCode::NOT_ACCEPTABLE, Json(serde_json::json!(""))).into_response(),
+    }
+}
+
+fn percent_encode(path_segment: &str) -> String {
+    path_segment
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
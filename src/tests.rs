@@ -1 +1,2 @@
 pub mod db_tests;
+pub mod username_tests;
@@ -1,3 +1,4 @@
+#![allow(clippy::bool_assert_comparison)]
 #[cfg(test)]
 use crate::db::db_operations::*;
 use tokio::test;
@@ -17,6 +18,7 @@ async fn create_connection_for_tests(
         .await
         .unwrap();
     connection.use_ns("test").use_db(db_name).await.unwrap();
+    migrate(&connection).await.unwrap();
     connection
 }
 
@@ -494,7 +496,12 @@ async fn test_is_banned_already_banned() {
 
     let _victim = ban(name_victim, name_judge, &connection).await.unwrap();
 
-    assert_eq!(is_banned(name_victim, name_judge, &connection).await, true);
+    assert_eq!(
+        is_banned(name_victim, name_judge, None, &connection)
+            .await
+            .is_some(),
+        true
+    );
 
     let _cleaning = connection.query("DELETE channel;").await;
 }
@@ -508,7 +515,1376 @@ async fn test_is_banned_nonbanned() {
     let _victim = create(name_victim, &connection).await.unwrap();
     let _judge = create(name_judge, &connection).await.unwrap();
 
-    assert_eq!(is_banned(name_victim, name_judge, &connection).await, false);
+    assert_eq!(
+        is_banned(name_victim, name_judge, None, &connection)
+            .await
+            .is_some(),
+        false
+    );
+
+    let _cleaning = connection.query("DELETE channel;").await;
+}
+
+#[test]
+async fn test_channel_response() {
+    let connection = create_connection_for_tests("test_channel_response").await;
+    let name_follower = &"Ahmet".to_string();
+    let name_followed = &"Kaan".to_string();
+
+    let _follower = create(name_follower, &connection).await.unwrap();
+    let followed = create(name_followed, &connection).await.unwrap();
+    let _follower = follow(name_follower, name_followed, &connection)
+        .await
+        .unwrap();
+
+    let followed_response = crate::response::ChannelResponse::from(
+        search_username(name_followed, &connection).await.unwrap(),
+    );
+
+    assert_eq!(followed_response.id, followed.id.unwrap().id.to_raw());
+    assert_eq!(followed_response.username, "Kaan");
+    assert_eq!(followed_response.follower_count, 1);
+    assert_eq!(followed_response.followed_count, 0);
+
+    let _cleaning = connection.query("DELETE channel;").await;
+}
+
+#[test]
+async fn test_list_followers_paginated() {
+    let connection = create_connection_for_tests("test_list_followers_paginated").await;
+    let name_followed = &"Kaan".to_string();
+    let names_follower = ["Mehmet", "Ahmet", "Zeynep"];
+
+    let _followed = create(name_followed, &connection).await.unwrap();
+    for name_follower in names_follower {
+        let name_follower = &name_follower.to_string();
+        let _follower = create(name_follower, &connection).await.unwrap();
+        let _follower = follow(name_follower, name_followed, &connection)
+            .await
+            .unwrap();
+    }
+
+    let pagination = crate::response::Pagination {
+        limit: Some(2),
+        ..Default::default()
+    };
+    let first_page = list_relation(
+        name_followed,
+        crate::Relation::Follower,
+        &pagination,
+        &connection,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(first_page.total, 3);
+    assert_eq!(first_page.items[0].username, "Ahmet");
+    assert_eq!(first_page.items[1].username, "Mehmet");
+
+    let _followed = unfollow(&"Mehmet".to_string(), name_followed, &connection)
+        .await
+        .unwrap();
+
+    let pagination = crate::response::Pagination {
+        cursor: first_page.next_cursor,
+        limit: Some(2),
+        ..Default::default()
+    };
+    let second_page = list_relation(
+        name_followed,
+        crate::Relation::Follower,
+        &pagination,
+        &connection,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(second_page.items.len(), 1);
+    assert_eq!(second_page.items[0].username, "Zeynep");
+    assert_eq!(second_page.next_cursor.is_none(), true);
+
+    let _cleaning = connection.query("DELETE channel; DELETE follow;").await;
+}
+
+#[test]
+async fn test_counts() {
+    let connection = create_connection_for_tests("test_counts").await;
+    let name_follower = &"Ahmet".to_string();
+    let name_followed = &"Kaan".to_string();
+
+    let _follower = create(name_follower, &connection).await.unwrap();
+    let _followed = create(name_followed, &connection).await.unwrap();
+
+    let _follower = follow(name_follower, name_followed, &connection)
+        .await
+        .unwrap();
+    let _followed = ban(name_followed, name_follower, &connection)
+        .await
+        .unwrap();
+
+    let follower_counts = counts(name_follower, &connection).await.unwrap();
+    let followed_counts = counts(name_followed, &connection).await.unwrap();
+
+    assert_eq!(follower_counts.followed_count, 1);
+    assert_eq!(follower_counts.banned_count, 1);
+    assert_eq!(followed_counts.follower_count, 1);
+    assert_eq!(followed_counts.banned_from_count, 1);
+
+    let _follower = delete(name_follower, &connection).await.unwrap();
+    let followed_counts = counts(name_followed, &connection).await.unwrap();
+
+    assert_eq!(followed_counts.follower_count, 0);
+    assert_eq!(followed_counts.banned_from_count, 0);
+
+    let _cleaning = connection.query("DELETE channel;").await;
+}
+
+#[test]
+async fn test_relationship() {
+    let connection = create_connection_for_tests("test_relationship").await;
+    let name_first = &"Ahmet".to_string();
+    let name_second = &"Kaan".to_string();
+
+    let _first = create(name_first, &connection).await.unwrap();
+    let _second = create(name_second, &connection).await.unwrap();
+
+    let _first = follow(name_first, name_second, &connection).await.unwrap();
+    let _first = ban(name_first, name_second, &connection).await.unwrap();
+
+    let relationship = relationship(name_first, name_second, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(relationship.follows, false);
+    assert_eq!(relationship.followed_by, false);
+    assert_eq!(relationship.banned, false);
+    assert_eq!(relationship.banned_by, true);
+
+    let _cleaning = connection.query("DELETE channel;").await;
+}
+
+#[test]
+async fn test_are_banned() {
+    let connection = create_connection_for_tests("test_are_banned").await;
+    let name_judge = &"Kaan".to_string();
+    let name_victim = &"Ahmet".to_string();
+    let name_innocent = &"Mehmet".to_string();
+
+    let _judge = create(name_judge, &connection).await.unwrap();
+    let _victim = create(name_victim, &connection).await.unwrap();
+    let _innocent = create(name_innocent, &connection).await.unwrap();
+
+    let _victim = ban(name_victim, name_judge, &connection).await.unwrap();
+
+    let usernames = vec![
+        name_victim.to_string(),
+        name_innocent.to_string(),
+        "Nobody".to_string(),
+    ];
+    let results = are_banned(&usernames, name_judge, None, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(results[name_victim], Some(crate::BanScope::Full));
+    assert_eq!(results[name_innocent], None);
+    assert_eq!(results["Nobody"], None);
+
+    let _cleaning = connection.query("DELETE channel;").await;
+}
+
+#[test]
+async fn test_batch_ban() {
+    let connection = create_connection_for_tests("test_batch_ban").await;
+    let name_judge = &"Kaan".to_string();
+    let name_victim = &"Ahmet".to_string();
+    let name_already_banned = &"Mehmet".to_string();
+
+    let _judge = create(name_judge, &connection).await.unwrap();
+    let _victim = create(name_victim, &connection).await.unwrap();
+    let _already_banned = create(name_already_banned, &connection).await.unwrap();
+
+    let _already_banned = ban(name_already_banned, name_judge, &connection)
+        .await
+        .unwrap();
+
+    let usernames = vec![
+        name_victim.to_string(),
+        name_already_banned.to_string(),
+        "Nobody".to_string(),
+    ];
+    let results = batch_ban(
+        &usernames,
+        name_judge,
+        crate::BanDetails::default(),
+        &connection,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(results[name_victim], "ok");
+    assert_eq!(results[name_already_banned], "already_banned");
+    assert_eq!(results["Nobody"], "not_found");
+    assert_eq!(
+        is_banned(name_victim, name_judge, None, &connection)
+            .await
+            .is_some(),
+        true
+    );
+
+    let victim = search_username(name_victim, &connection).await.unwrap();
+    let judge = search_username(name_judge, &connection).await.unwrap();
+
+    assert_eq!(victim.banned_from_list, vec![judge.id.unwrap().id]);
+    assert_eq!(judge.banned_count, 2);
+
+    let _cleaning = connection.query("DELETE channel;").await;
+}
+
+#[test]
+async fn test_try_errors() {
+    let connection = create_connection_for_tests("test_try_errors").await;
+    let name_follower = &"Ahmet".to_string();
+    let name_followed = &"Kaan".to_string();
+
+    let _follower = try_create(name_follower, &connection).await.unwrap();
+    let _followed = try_create(name_followed, &connection).await.unwrap();
+
+    assert_eq!(
+        try_create(name_follower, &connection).await,
+        Err(crate::error::ApiError::ChannelAlreadyExists)
+    );
+    assert_eq!(
+        try_unfollow(name_follower, name_followed, &connection).await,
+        Err(crate::error::ApiError::NotFollower)
+    );
+    assert_eq!(
+        try_ban(
+            &"Nobody".to_string(),
+            name_followed,
+            crate::BanDetails::default(),
+            &connection
+        )
+        .await,
+        Err(crate::error::ApiError::ChannelNotExists)
+    );
+    assert_eq!(
+        try_change_username(name_followed, name_follower, &connection).await,
+        Err(crate::error::ApiError::ChannelAlreadyExists)
+    );
+
+    let _cleaning = connection.query("DELETE channel;").await;
+}
+
+#[test]
+async fn test_create_case_insensitive() {
+    let connection = create_connection_for_tests("test_create_case_insensitive").await;
+    let name = &"Ahmet".to_string();
+
+    let created = create(name, &connection).await.unwrap();
+
+    assert_eq!(
+        create(&"ahmet".to_string(), &connection).await.is_none(),
+        true
+    );
+    assert_eq!(
+        search_username(&"AHMET".to_string(), &connection)
+            .await
+            .unwrap()
+            .id,
+        created.id
+    );
+    assert_eq!(created.username, "Ahmet");
+
+    let _cleaning = connection.query("DELETE channel;").await;
+}
+
+#[test]
+async fn test_change_username_history() {
+    let connection = create_connection_for_tests("test_change_username_history").await;
+    let name = &"Ahmet".to_string();
+    let name_updated = &"Kaan".to_string();
+
+    let created = create(name, &connection).await.unwrap();
+    let _changed = change_username(name_updated, name, &connection)
+        .await
+        .unwrap();
+
+    let (resolved, redirected) = resolve_username(name, &connection).await.unwrap();
+
+    assert_eq!(resolved.id, created.id);
+    assert_eq!(redirected, true);
+    assert_eq!(
+        username_history(name_updated, &connection).await.unwrap()[0].username,
+        "Ahmet"
+    );
+    assert_eq!(
+        try_create(name, &connection).await,
+        Err(crate::error::ApiError::UsernameQuarantined)
+    );
+    assert_eq!(
+        try_change_username(&"Mehmet".to_string(), name_updated, &connection).await,
+        Err(crate::error::ApiError::RenameCooldown)
+    );
+
+    let _cleaning = connection.query("DELETE channel;").await;
+}
+
+#[test]
+async fn test_update_profile() {
+    let connection = create_connection_for_tests("test_update_profile").await;
+    let name = &"Ahmet".to_string();
+
+    let created = create(name, &connection).await.unwrap();
+    let metadata = std::collections::BTreeMap::from([("country".to_string(), "TR".to_string())]);
+    let updated = update_profile(
+        name,
+        Some("Ahmet Kaan".to_string()),
+        Some(metadata.clone()),
+        &connection,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(updated.display_name, Some("Ahmet Kaan".to_string()));
+    assert_eq!(updated.metadata, metadata);
+    assert_eq!(updated.created_at, created.created_at);
+    assert_eq!(updated.updated_at >= created.updated_at, true);
 
     let _cleaning = connection.query("DELETE channel;").await;
 }
+
+#[test]
+async fn test_follow_details() {
+    let connection = create_connection_for_tests("test_follow_details").await;
+    let name_follower = &"Ahmet".to_string();
+    let name_followed = &"Kaan".to_string();
+    let name_updated = &"Mehmet".to_string();
+
+    let _follower = create(name_follower, &connection).await.unwrap();
+    let _followed = create(name_followed, &connection).await.unwrap();
+    let _follower = try_follow(
+        name_follower,
+        name_followed,
+        Some("raid".to_string()),
+        &connection,
+    )
+    .await
+    .unwrap();
+    let _follower = change_username(name_follower, name_updated, &connection)
+        .await
+        .unwrap();
+
+    let pagination = crate::response::Pagination {
+        sort: crate::response::ListSort::Time,
+        ..Default::default()
+    };
+    let followers = list_relation(
+        name_followed,
+        crate::Relation::Follower,
+        &pagination,
+        &connection,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(followers.items[0].username, "Mehmet");
+    assert_eq!(followers.items[0].source, Some("raid".to_string()));
+    assert_eq!(followers.items[0].since.is_some(), true);
+
+    let following = relationship(name_updated, name_followed, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(following.follows_since, followers.items[0].since);
+    assert_eq!(following.followed_by_since, None);
+
+    let _follower = unfollow(name_updated, name_followed, &connection)
+        .await
+        .unwrap();
+    let unfollowed = relationship(name_updated, name_followed, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(unfollowed.follows_since, None);
+
+    let _cleaning = connection.query("DELETE channel; DELETE follow;").await;
+}
+
+#[test]
+async fn test_ban_details() {
+    let connection = create_connection_for_tests("test_ban_details").await;
+    let name_victim = &"Ahmet".to_string();
+    let name_judge = &"Kaan".to_string();
+
+    let _victim = create(name_victim, &connection).await.unwrap();
+    let _judge = create(name_judge, &connection).await.unwrap();
+    let details = crate::BanDetails {
+        reason: Some("spam".to_string()),
+        moderator: Some("Mehmet".to_string()),
+        ..Default::default()
+    };
+    let _victim = try_ban(name_victim, name_judge, details, &connection)
+        .await
+        .unwrap();
+
+    let banned = list_relation(
+        name_judge,
+        crate::Relation::Banned,
+        &crate::response::Pagination::default(),
+        &connection,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(banned.items[0].username, "Ahmet");
+    assert_eq!(banned.items[0].reason, Some("spam".to_string()));
+    assert_eq!(banned.items[0].moderator, Some("Mehmet".to_string()));
+    assert_eq!(banned.items[0].since.is_some(), true);
+
+    let judged = relationship(name_judge, name_victim, &connection)
+        .await
+        .unwrap();
+    let ban_details = judged.banned_details.unwrap();
+
+    assert_eq!(ban_details.reason, Some("spam".to_string()));
+    assert_eq!(Some(ban_details.since), banned.items[0].since);
+
+    let _victim = unban(name_victim, name_judge, &connection).await.unwrap();
+    let judged = relationship(name_judge, name_victim, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(judged.banned_details, None);
+
+    let _cleaning = connection.query("DELETE channel; DELETE ban;").await;
+}
+
+#[test]
+async fn test_temporary_ban() {
+    let connection = create_connection_for_tests("test_temporary_ban").await;
+    let name_victim = &"Ahmet".to_string();
+    let name_judge = &"Kaan".to_string();
+
+    let _victim = create(name_victim, &connection).await.unwrap();
+    let _judge = create(name_judge, &connection).await.unwrap();
+    let details = crate::BanDetails {
+        duration_seconds: Some(1),
+        ..Default::default()
+    };
+    let _victim = try_ban(name_victim, name_judge, details, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        is_banned(name_victim, name_judge, None, &connection)
+            .await
+            .is_some(),
+        true
+    );
+
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+
+    assert_eq!(
+        is_banned(name_victim, name_judge, None, &connection)
+            .await
+            .is_some(),
+        false
+    );
+    assert_eq!(remove_expired_bans(&connection).await, 1);
+
+    let judge = search_username(name_judge, &connection).await.unwrap();
+
+    assert_eq!(judge.banned_list.is_empty(), true);
+    assert_eq!(judge.banned_count, 0);
+
+    let _cleaning = connection.query("DELETE channel; DELETE ban;").await;
+}
+
+#[test]
+async fn test_mute() {
+    let connection = create_connection_for_tests("test_mute").await;
+    let name_victim = &"Ahmet".to_string();
+    let name_judge = &"Kaan".to_string();
+
+    let _victim = create(name_victim, &connection).await.unwrap();
+    let _judge = create(name_judge, &connection).await.unwrap();
+    let _judge = mute(name_victim, name_judge, &connection).await.unwrap();
+
+    assert_eq!(is_muted(name_victim, name_judge, &connection).await, true);
+    assert_eq!(
+        is_banned(name_victim, name_judge, None, &connection)
+            .await
+            .is_some(),
+        false
+    );
+    assert_eq!(
+        try_mute(name_victim, name_judge, None, &connection).await,
+        Err(crate::error::ApiError::AlreadyMuted)
+    );
+
+    let muted = relationship(name_victim, name_judge, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(muted.muted_by, true);
+    assert_eq!(muted.muted, false);
+
+    let _judge = delete(name_judge, &connection).await.unwrap();
+    let victim = search_username(name_victim, &connection).await.unwrap();
+
+    assert_eq!(victim.muted_from_list.is_empty(), true);
+
+    let _cleaning = connection.query("DELETE channel; DELETE mute;").await;
+}
+
+#[test]
+async fn test_block() {
+    let connection = create_connection_for_tests("test_block").await;
+    let name_blocker = &"Ahmet".to_string();
+    let name_blocked = &"Kaan".to_string();
+
+    let _blocker = create(name_blocker, &connection).await.unwrap();
+    let _blocked = create(name_blocked, &connection).await.unwrap();
+    let _blocker = follow(name_blocker, name_blocked, &connection)
+        .await
+        .unwrap();
+    let _blocked = follow(name_blocked, name_blocker, &connection)
+        .await
+        .unwrap();
+
+    let blocker = block(name_blocker, name_blocked, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(blocker.follower_list.is_empty(), true);
+    assert_eq!(blocker.followed_list.is_empty(), true);
+    assert_eq!(
+        is_blocked(name_blocked, name_blocker, &connection).await,
+        true
+    );
+    assert_eq!(
+        try_follow(name_blocked, name_blocker, None, &connection).await,
+        Err(crate::error::ApiError::Blocked)
+    );
+    assert_eq!(
+        try_follow(name_blocker, name_blocked, None, &connection).await,
+        Err(crate::error::ApiError::Blocked)
+    );
+
+    let _blocker = unblock(name_blocker, name_blocked, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        is_blocked(name_blocked, name_blocker, &connection).await,
+        false
+    );
+    assert_eq!(
+        follow(name_blocked, name_blocker, &connection)
+            .await
+            .is_some(),
+        true
+    );
+
+    let _cleaning = connection.query("DELETE channel; DELETE follow;").await;
+}
+
+#[test]
+async fn test_ban_enforcement() {
+    let connection = create_connection_for_tests("test_ban_enforcement").await;
+    let name_victim = &"Ahmet".to_string();
+    let name_judge = &"Kaan".to_string();
+
+    let _victim = create(name_victim, &connection).await.unwrap();
+    let _judge = create(name_judge, &connection).await.unwrap();
+    let _victim = follow(name_victim, name_judge, &connection).await.unwrap();
+    let _judge = follow(name_judge, name_victim, &connection).await.unwrap();
+    let _victim = ban(name_victim, name_judge, &connection).await.unwrap();
+
+    assert_eq!(
+        is_follower(name_victim, name_judge, &connection).await,
+        false
+    );
+    assert_eq!(
+        is_follower(name_judge, name_victim, &connection).await,
+        true
+    );
+    assert_eq!(
+        try_follow(name_victim, name_judge, None, &connection).await,
+        Err(crate::error::ApiError::BannedByChannel)
+    );
+
+    let _victim = unban(name_victim, name_judge, &connection).await.unwrap();
+
+    assert_eq!(
+        follow(name_victim, name_judge, &connection).await.is_some(),
+        true
+    );
+
+    let _cleaning = connection.query("DELETE channel; DELETE follow;").await;
+}
+
+#[cfg(test)]
+struct NoFollowingOfficial;
+
+#[cfg(test)]
+#[axum::async_trait]
+impl crate::db::policy::RelationshipPolicy for NoFollowingOfficial {
+    async fn check(
+        &self,
+        action: crate::db::policy::Action,
+        _actor: &crate::Channel,
+        target: &crate::Channel,
+        _db: &surrealdb::Surreal<surrealdb::engine::remote::ws::Client>,
+    ) -> Result<(), crate::error::ApiError> {
+        match action == crate::db::policy::Action::Follow && target.username == "Official" {
+            true => Err(crate::error::ApiError::PolicyViolation(
+                crate::error::PolicyViolation {
+                    code: "official_not_followable",
+                    message: "Official Not Followable".to_string(),
+                },
+            )),
+            false => Ok(()),
+        }
+    }
+}
+
+#[test]
+async fn test_policies() {
+    let connection = create_connection_for_tests("test_policies").await;
+    let name_channel = &"Ahmet".to_string();
+    let name_official = &"Official".to_string();
+
+    crate::db::policy::register_policy(NoFollowingOfficial);
+
+    let _channel = create(name_channel, &connection).await.unwrap();
+    let _official = create(name_official, &connection).await.unwrap();
+
+    assert_eq!(
+        try_follow(name_channel, name_channel, None, &connection).await,
+        Err(crate::error::ApiError::SelfRelation)
+    );
+    assert_eq!(
+        try_ban(
+            name_channel,
+            name_channel,
+            crate::BanDetails::default(),
+            &connection
+        )
+        .await,
+        Err(crate::error::ApiError::SelfRelation)
+    );
+    assert_eq!(
+        try_follow(name_channel, name_official, None, &connection)
+            .await
+            .unwrap_err()
+            .code(),
+        "official_not_followable"
+    );
+    assert_eq!(
+        follow(name_official, name_channel, &connection)
+            .await
+            .is_some(),
+        true
+    );
+
+    let _cleaning = connection.query("DELETE channel; DELETE follow;").await;
+}
+
+#[test]
+async fn test_follow_request() {
+    let connection = create_connection_for_tests("test_follow_request").await;
+    let name_follower = &"Ahmet".to_string();
+    let name_followed = &"Kaan".to_string();
+
+    let _follower = create(name_follower, &connection).await.unwrap();
+    let _followed = create(name_followed, &connection).await.unwrap();
+    let _followed = update_settings(
+        name_followed,
+        Some(crate::AcceptFollows::Approval),
+        None,
+        None,
+        None,
+        &connection,
+    )
+    .await
+    .unwrap();
+
+    let outcome = try_follow(
+        name_follower,
+        name_followed,
+        Some("search".to_string()),
+        &connection,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(outcome.status(), "requested");
+    assert_eq!(
+        is_follower(name_follower, name_followed, &connection).await,
+        false
+    );
+    assert_eq!(
+        try_follow(name_follower, name_followed, None, &connection).await,
+        Err(crate::error::ApiError::AlreadyRequested)
+    );
+
+    let requests = list_follow_requests(
+        name_followed,
+        &crate::response::Pagination::default(),
+        &connection,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(requests.items.len(), 1);
+    assert_eq!(requests.items[0].username, *name_follower);
+    assert_eq!(requests.items[0].source, Some("search".to_string()));
+
+    let _follower = approve_follow_request(name_follower, name_followed, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        is_follower(name_follower, name_followed, &connection).await,
+        true
+    );
+    assert_eq!(
+        try_remove_follow_request(name_follower, name_followed, &connection).await,
+        Err(crate::error::ApiError::NotRequested)
+    );
+
+    let _follower = unfollow(name_follower, name_followed, &connection)
+        .await
+        .unwrap();
+    let _follower = follow(name_follower, name_followed, &connection)
+        .await
+        .unwrap();
+    let _follower = cancel_follow_request(name_follower, name_followed, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        list_follow_requests(
+            name_followed,
+            &crate::response::Pagination::default(),
+            &connection,
+        )
+        .await
+        .unwrap()
+        .items
+        .len(),
+        0
+    );
+
+    let _cleaning = connection
+        .query("DELETE channel; DELETE follow; DELETE follow_request;")
+        .await;
+}
+
+#[test]
+async fn test_settings() {
+    let connection = create_connection_for_tests("test_settings").await;
+    let name_channel = &"Ahmet".to_string();
+    let name_other = &"Kaan".to_string();
+
+    let _channel = create(name_channel, &connection).await.unwrap();
+    let _other = create(name_other, &connection).await.unwrap();
+
+    assert_eq!(
+        settings(name_channel, &connection).await.unwrap(),
+        crate::ChannelSettings::default()
+    );
+
+    let channel = update_settings(
+        name_channel,
+        Some(crate::AcceptFollows::Nobody),
+        Some(true),
+        None,
+        Some(false),
+        &connection,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        channel.settings.accept_follows,
+        crate::AcceptFollows::Nobody
+    );
+    assert_eq!(channel.settings.hide_following_list, false);
+    assert_eq!(
+        try_follow(name_other, name_channel, None, &connection).await,
+        Err(crate::error::ApiError::FollowsClosed)
+    );
+
+    let pagination = crate::response::Pagination::default();
+
+    assert_eq!(
+        try_list_relation(
+            name_channel,
+            crate::Relation::Follower,
+            &pagination,
+            Some(name_other),
+            &connection
+        )
+        .await,
+        Err(crate::error::ApiError::ListHidden)
+    );
+    assert_eq!(
+        try_list_relation(
+            name_channel,
+            crate::Relation::Follower,
+            &pagination,
+            Some(name_channel),
+            &connection
+        )
+        .await
+        .is_ok(),
+        true
+    );
+
+    let _other = ban(name_other, name_channel, &connection).await.unwrap();
+
+    assert_eq!(
+        try_list_relation(
+            name_other,
+            crate::Relation::BannedFrom,
+            &pagination,
+            Some(name_other),
+            &connection
+        )
+        .await
+        .unwrap()
+        .items
+        .len(),
+        0
+    );
+    assert_eq!(
+        relationship(name_other, name_channel, &connection)
+            .await
+            .unwrap()
+            .banned_by,
+        false
+    );
+    assert_eq!(
+        relationship(name_channel, name_other, &connection)
+            .await
+            .unwrap()
+            .banned,
+        true
+    );
+    assert_eq!(
+        is_banned(name_other, name_channel, None, &connection)
+            .await
+            .is_some(),
+        true
+    );
+
+    let _cleaning = connection.query("DELETE channel; DELETE ban;").await;
+}
+
+#[test]
+async fn test_moderators() {
+    let connection = create_connection_for_tests("test_moderators").await;
+    let name_channel = &"Ahmet".to_string();
+    let name_moderator = &"Kaan".to_string();
+    let name_victim = &"Mehmet".to_string();
+
+    let _channel = create(name_channel, &connection).await.unwrap();
+    let _moderator = create(name_moderator, &connection).await.unwrap();
+    let _victim = create(name_victim, &connection).await.unwrap();
+
+    assert_eq!(
+        try_moderator_ban(
+            name_victim,
+            name_channel,
+            name_moderator,
+            crate::BanDetails::default(),
+            &connection
+        )
+        .await,
+        Err(crate::error::ApiError::ModeratorRequired)
+    );
+
+    let channel = add_moderator(name_moderator, name_channel, &connection)
+        .await
+        .unwrap();
+    let moderator = search_username(name_moderator, &connection).await.unwrap();
+
+    assert_eq!(
+        channel.moderator_list,
+        vec![moderator.id.clone().unwrap().id]
+    );
+    assert_eq!(moderator.moderating_list, vec![channel.id.unwrap().id]);
+    assert_eq!(
+        is_moderator(name_moderator, name_channel, &connection).await,
+        true
+    );
+    assert_eq!(
+        try_add_moderator(name_moderator, name_channel, &connection).await,
+        Err(crate::error::ApiError::AlreadyModerator)
+    );
+
+    let _victim = try_moderator_ban(
+        name_victim,
+        name_channel,
+        name_moderator,
+        crate::BanDetails {
+            reason: Some("Spam".to_string()),
+            ..Default::default()
+        },
+        &connection,
+    )
+    .await
+    .unwrap();
+    let _victim = try_moderator_mute(name_victim, name_channel, name_moderator, None, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        is_banned(name_victim, name_channel, None, &connection)
+            .await
+            .is_some(),
+        true
+    );
+    assert_eq!(is_muted(name_victim, name_channel, &connection).await, true);
+
+    let pagination = crate::response::Pagination::default();
+    let banned = list_relation(
+        name_channel,
+        crate::Relation::Banned,
+        &pagination,
+        &connection,
+    )
+    .await
+    .unwrap();
+    let muted = list_relation(
+        name_channel,
+        crate::Relation::Muted,
+        &pagination,
+        &connection,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(banned.items[0].moderator, Some(name_moderator.to_string()));
+    assert_eq!(muted.items[0].moderator, Some(name_moderator.to_string()));
+
+    let _channel = remove_moderator(name_moderator, name_channel, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        try_moderator_unban(name_victim, name_channel, name_moderator, &connection).await,
+        Err(crate::error::ApiError::ModeratorRequired)
+    );
+
+    let _cleaning = connection
+        .query("DELETE channel; DELETE ban; DELETE mute;")
+        .await;
+}
+
+#[test]
+async fn test_global_ban() {
+    let connection = create_connection_for_tests("test_global_ban").await;
+    let name_victim = &"Ahmet".to_string();
+    let name_channel = &"Kaan".to_string();
+
+    let _victim = create(name_victim, &connection).await.unwrap();
+    let _channel = create(name_channel, &connection).await.unwrap();
+
+    let global_ban = global_ban(name_victim, Some("Spam".to_string()), None, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(global_ban.reason, Some("Spam".to_string()));
+    assert_eq!(
+        is_banned(name_victim, name_channel, None, &connection)
+            .await
+            .is_some(),
+        true
+    );
+    assert_eq!(
+        are_banned(&[name_victim.to_string()], name_channel, None, &connection)
+            .await
+            .unwrap()[name_victim]
+            .is_some(),
+        true
+    );
+    assert_eq!(
+        relationship(name_channel, name_victim, &connection)
+            .await
+            .unwrap()
+            .banned,
+        true
+    );
+    let banned_from = is_banned_from(
+        name_victim,
+        &[name_channel.to_string(), "Nobody".to_string()],
+        None,
+        &connection,
+    )
+    .await
+    .unwrap();
+    assert_eq!(banned_from[name_channel], Some(crate::BanScope::Full));
+    assert_eq!(banned_from["Nobody"], None);
+    assert_eq!(
+        try_follow(name_victim, name_channel, None, &connection).await,
+        Err(crate::error::ApiError::GloballyBanned)
+    );
+    assert_eq!(
+        try_global_ban(name_victim, None, None, &connection).await,
+        Err(crate::error::ApiError::AlreadyGloballyBanned)
+    );
+    assert_eq!(list_global_bans(&connection).await.len(), 1);
+
+    let _global_ban = global_unban(name_victim, &connection).await.unwrap();
+
+    assert_eq!(
+        is_banned(name_victim, name_channel, None, &connection)
+            .await
+            .is_some(),
+        false
+    );
+    assert_eq!(
+        follow(name_victim, name_channel, &connection)
+            .await
+            .is_some(),
+        true
+    );
+
+    let _cleaning = connection
+        .query("DELETE channel; DELETE follow; DELETE global_ban;")
+        .await;
+}
+
+#[test]
+async fn test_ban_lists() {
+    let connection = create_connection_for_tests("test_ban_lists").await;
+    let name_owner = &"Ahmet".to_string();
+    let name_subscriber = &"Kaan".to_string();
+    let name_victim = &"Mehmet".to_string();
+    let name_list = &"Trolls".to_string();
+
+    let _owner = create(name_owner, &connection).await.unwrap();
+    let _subscriber = create(name_subscriber, &connection).await.unwrap();
+    let _victim = create(name_victim, &connection).await.unwrap();
+
+    let _ban_list = create_ban_list(name_owner, name_list, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        try_create_ban_list(name_owner, name_list, &connection).await,
+        Err(crate::error::ApiError::BanListAlreadyExists)
+    );
+
+    let ban_list = add_to_ban_list(name_owner, name_list, name_victim, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(ban_list.entries, vec![name_victim.to_string()]);
+    assert_eq!(
+        try_subscribe_ban_list(name_subscriber, name_owner, name_list, &connection).await,
+        Err(crate::error::ApiError::BanListNotPublished)
+    );
+
+    let _ban_list = publish_ban_list(name_owner, name_list, true, &connection)
+        .await
+        .unwrap();
+    let _subscriber = subscribe_ban_list(name_subscriber, name_owner, name_list, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        is_banned(name_victim, name_subscriber, None, &connection)
+            .await
+            .is_some(),
+        true
+    );
+    assert_eq!(
+        are_banned(
+            &[name_victim.to_string()],
+            name_subscriber,
+            None,
+            &connection
+        )
+        .await
+        .unwrap()[name_victim]
+            .is_some(),
+        true
+    );
+
+    let subscriber_relationship = relationship(name_subscriber, name_victim, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(subscriber_relationship.banned, true);
+    assert_eq!(subscriber_relationship.banned_details, None);
+    assert_eq!(
+        relationship(name_victim, name_subscriber, &connection)
+            .await
+            .unwrap()
+            .banned_by,
+        true
+    );
+    assert_eq!(
+        is_banned(name_victim, name_owner, None, &connection)
+            .await
+            .is_some(),
+        false
+    );
+
+    let _subscriber = allow(name_victim, name_subscriber, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        is_banned(name_victim, name_subscriber, None, &connection)
+            .await
+            .is_some(),
+        false
+    );
+
+    let _subscriber = disallow(name_victim, name_subscriber, &connection)
+        .await
+        .unwrap();
+    let _ban_list = publish_ban_list(name_owner, name_list, false, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        is_banned(name_victim, name_subscriber, None, &connection)
+            .await
+            .is_some(),
+        false
+    );
+
+    let _ban_list = delete_ban_list(name_owner, name_list, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        ban_list_subscriptions(name_subscriber, &connection)
+            .await
+            .unwrap()
+            .len(),
+        0
+    );
+
+    let _cleaning = connection.query("DELETE channel; DELETE ban_list;").await;
+}
+
+#[test]
+async fn test_organizations() {
+    let connection = create_connection_for_tests("test_organizations").await;
+    let name_organization = &"Ahmet".to_string();
+    let name_member = &"Kaan".to_string();
+    let name_victim = &"Mehmet".to_string();
+
+    let _organization = create(name_organization, &connection).await.unwrap();
+    let _member = create(name_member, &connection).await.unwrap();
+    let _victim = create(name_victim, &connection).await.unwrap();
+
+    let _organization = add_member(name_member, name_organization, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        is_member(name_member, name_organization, &connection).await,
+        true
+    );
+    assert_eq!(
+        try_add_member(name_member, name_organization, &connection).await,
+        Err(crate::error::ApiError::AlreadyMember)
+    );
+
+    let _organization = ban(name_victim, name_organization, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        is_banned(name_victim, name_member, None, &connection)
+            .await
+            .is_some(),
+        true
+    );
+    assert_eq!(
+        are_banned(&[name_victim.to_string()], name_member, None, &connection)
+            .await
+            .unwrap()[name_victim]
+            .is_some(),
+        true
+    );
+    assert_eq!(
+        is_banned_from(name_victim, &[name_member.to_string()], None, &connection)
+            .await
+            .unwrap()[name_member],
+        Some(crate::BanScope::Full)
+    );
+    assert_eq!(
+        relationship(name_member, name_victim, &connection)
+            .await
+            .unwrap()
+            .banned,
+        true
+    );
+    assert_eq!(
+        relationship(name_victim, name_member, &connection)
+            .await
+            .unwrap()
+            .banned_by,
+        true
+    );
+
+    let banned = list_relation(
+        name_member,
+        crate::Relation::Banned,
+        &crate::response::Pagination::default(),
+        &connection,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(banned.items.len(), 1);
+    assert_eq!(
+        banned.items[0].inherited_from,
+        Some(name_organization.to_string())
+    );
+
+    let _organization = remove_member(name_member, name_organization, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        is_banned(name_victim, name_member, None, &connection)
+            .await
+            .is_some(),
+        false
+    );
+
+    let _cleaning = connection.query("DELETE channel; DELETE ban;").await;
+}
+
+#[test]
+async fn test_ban_scopes() {
+    let connection = create_connection_for_tests("test_ban_scopes").await;
+    let name_victim = &"Ahmet".to_string();
+    let name_judge = &"Kaan".to_string();
+
+    let _victim = create(name_victim, &connection).await.unwrap();
+    let _judge = create(name_judge, &connection).await.unwrap();
+
+    let details = crate::BanDetails {
+        scope: crate::BanScope::Chat,
+        ..Default::default()
+    };
+    let _victim = try_ban(name_victim, name_judge, details, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        is_banned(name_victim, name_judge, None, &connection).await,
+        Some(crate::BanScope::Chat)
+    );
+    assert_eq!(
+        is_banned(
+            name_victim,
+            name_judge,
+            Some(crate::BanScope::Full),
+            &connection
+        )
+        .await,
+        None
+    );
+    assert_eq!(
+        are_banned(
+            &[name_victim.to_string()],
+            name_judge,
+            Some(crate::BanScope::Full),
+            &connection
+        )
+        .await
+        .unwrap()[name_victim],
+        None
+    );
+    assert_eq!(
+        is_banned_from(name_victim, &[name_judge.to_string()], None, &connection)
+            .await
+            .unwrap()[name_judge],
+        Some(crate::BanScope::Chat)
+    );
+
+    let relationship = relationship(name_judge, name_victim, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(relationship.banned_scope, Some(crate::BanScope::Chat));
+
+    let _victim = unban(name_victim, name_judge, &connection).await.unwrap();
+    let _victim = ban(name_victim, name_judge, &connection).await.unwrap();
+
+    assert_eq!(
+        is_banned(
+            name_victim,
+            name_judge,
+            Some(crate::BanScope::Chat),
+            &connection
+        )
+        .await,
+        Some(crate::BanScope::Full)
+    );
+
+    let _cleaning = connection.query("DELETE channel; DELETE ban;").await;
+}
+
+#[test]
+async fn test_chat_ban_follows() {
+    let connection = create_connection_for_tests("test_chat_ban_follows").await;
+    let name_victim = &"Ahmet".to_string();
+    let name_judge = &"Kaan".to_string();
+
+    let _victim = create(name_victim, &connection).await.unwrap();
+    let _judge = create(name_judge, &connection).await.unwrap();
+    let _victim = follow(name_victim, name_judge, &connection).await.unwrap();
+
+    let details = crate::BanDetails {
+        scope: crate::BanScope::Chat,
+        ..Default::default()
+    };
+    let _victim = try_ban(name_victim, name_judge, details, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        is_follower(name_victim, name_judge, &connection).await,
+        true
+    );
+
+    let _judge = unfollow(name_victim, name_judge, &connection)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        try_follow(name_victim, name_judge, None, &connection)
+            .await
+            .is_ok(),
+        true
+    );
+
+    let _victim = unban(name_victim, name_judge, &connection).await.unwrap();
+    let _victim = ban(name_victim, name_judge, &connection).await.unwrap();
+
+    assert_eq!(
+        is_follower(name_victim, name_judge, &connection).await,
+        false
+    );
+    assert_eq!(
+        try_follow(name_victim, name_judge, None, &connection).await,
+        Err(crate::error::ApiError::BannedByChannel)
+    );
+
+    let _cleaning = connection
+        .query("DELETE channel; DELETE follow; DELETE ban;")
+        .await;
+}
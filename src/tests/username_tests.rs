@@ -0,0 +1,54 @@
+#![allow(clippy::bool_assert_comparison)]
+#[cfg(test)]
+use crate::{username::*, UsernameRules};
+
+#[test]
+fn test_validate_username() {
+    let rules = UsernameRules::default();
+
+    assert_eq!(validate_username("Ahmet", &rules).is_ok(), true);
+    assert_eq!(validate_username("Kaan_1907", &rules).is_ok(), true);
+}
+
+#[test]
+fn test_validate_username_invalid() {
+    let rules = UsernameRules::default();
+
+    assert_eq!(
+        validate_username("", &rules).unwrap_err().code,
+        "username_too_short"
+    );
+    assert_eq!(
+        validate_username(&"a".repeat(10_000), &rules)
+            .unwrap_err()
+            .code,
+        "username_too_long"
+    );
+    assert_eq!(
+        validate_username("Ahmet Kaan", &rules).unwrap_err().code,
+        "username_invalid_character"
+    );
+    assert_eq!(
+        validate_username("Follow", &rules).unwrap_err().code,
+        "username_reserved"
+    );
+}
+
+#[test]
+fn test_normalize_username() {
+    assert_eq!(normalize_username("Ahmet"), normalize_username("aHMET"));
+    assert_eq!(normalize_username("Ｋａａｎ"), "kaan");
+    assert_ne!(
+        normalize_username("ahmet"),
+        normalize_username("\u{0430}hmet")
+    );
+}
+
+#[test]
+fn test_username_skeleton() {
+    assert_eq!(
+        username_skeleton("ahmet"),
+        username_skeleton("\u{0430}hmet")
+    );
+    assert_ne!(username_skeleton("ahmet"), username_skeleton("kaan"));
+}
@@ -0,0 +1,63 @@
+use unicode_normalization::UnicodeNormalization;
+use unicode_security::skeleton;
+
+use crate::{error::ValidationError, UsernameRules};
+
+/// NFKC with case folding, used for uniqueness and lookups while the
+/// stored `username` keeps its display casing.
+pub fn normalize_username(username: &str) -> String {
+    username
+        .nfkc()
+        .flat_map(char::to_lowercase)
+        .collect::<String>()
+        .nfkc()
+        .collect()
+}
+
+/// UTS 39 skeleton of the normalized username, equal for look-alikes such
+/// as Latin `a` and Cyrillic `а`.
+pub fn username_skeleton(username: &str) -> String {
+    skeleton(&normalize_username(username)).collect()
+}
+
+pub fn validate_username(username: &str, rules: &UsernameRules) -> Result<(), ValidationError> {
+    let length = username.chars().count();
+    if length < rules.min_length {
+        return Err(ValidationError {
+            field: "username",
+            code: "username_too_short",
+            message: format!("Username Must Be At Least {} Characters", rules.min_length),
+        });
+    }
+    if length > rules.max_length {
+        return Err(ValidationError {
+            field: "username",
+            code: "username_too_long",
+            message: format!("Username Must Be At Most {} Characters", rules.max_length),
+        });
+    }
+    if let Some(character) = username.chars().find(|character| {
+        !rules
+            .allowed_characters
+            .iter()
+            .any(|class| class.contains(*character))
+    }) {
+        return Err(ValidationError {
+            field: "username",
+            code: "username_invalid_character",
+            message: format!("Username Contains Invalid Character {:?}", character),
+        });
+    }
+    if rules
+        .reserved
+        .iter()
+        .any(|reserved| normalize_username(reserved) == normalize_username(username))
+    {
+        return Err(ValidationError {
+            field: "username",
+            code: "username_reserved",
+            message: format!("Username {} Is Reserved", username),
+        });
+    }
+    Ok(())
+}
@@ -1,7 +1,11 @@
+use std::sync::OnceLock;
+
 use axum_server::tls_rustls::RustlsConfig;
 use tokio::{fs::File, io::AsyncReadExt};
 
-use crate::{DataBaseConfig, ServerConfig};
+use crate::{ApiConfig, CharacterClass, DataBaseConfig, ServerConfig};
+
+static API_CONFIG: OnceLock<ApiConfig> = OnceLock::new();
 
 pub async fn tls_config() -> RustlsConfig {
     RustlsConfig::from_pem_file("certificates/fullchain.pem", "certificates/privkey.pem")
@@ -49,9 +53,69 @@ pub async fn server_config() -> ServerConfig {
         let dirty: Vec<&str> = element.split('=').collect();
         configs_cleaned.push(dirty[1]);
     }
-    
+
     ServerConfig {
         ip_address: configs_cleaned[0].parse().unwrap(),
         port: configs_cleaned[1].parse().unwrap(),
     }
 }
+
+/// Loaded once, missing file or keys fall back to `ApiConfig::default()`.
+pub fn api_config() -> &'static ApiConfig {
+    API_CONFIG.get_or_init(|| match std::fs::read_to_string("configs/api_config.txt") {
+        Ok(config_unparsed) => parse_api_config(&config_unparsed),
+        Err(err_val) => {
+            eprintln!("Error: API Config | {}", err_val);
+            ApiConfig::default()
+        }
+    })
+}
+
+fn parse_api_config(config_unparsed: &str) -> ApiConfig {
+    let mut api_config = ApiConfig::default();
+    for element in config_unparsed.split_terminator('\n') {
+        let (key, value) = match element.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => continue,
+        };
+        match key {
+            "max_batch_size" => api_config.max_batch_size = value.parse().unwrap(),
+            "batch_chunk_size" => api_config.batch_chunk_size = value.parse().unwrap(),
+            "username_min_length" => api_config.username_rules.min_length = value.parse().unwrap(),
+            "username_max_length" => api_config.username_rules.max_length = value.parse().unwrap(),
+            "username_characters" => {
+                api_config.username_rules.allowed_characters = value
+                    .split(',')
+                    .map(|name| CharacterClass::from_name(name.trim()).unwrap())
+                    .collect()
+            }
+            "confusable_detection" => api_config.confusable_detection = value.parse().unwrap(),
+            "username_quarantine_seconds" => {
+                api_config.username_quarantine_seconds = value.parse().unwrap()
+            }
+            "rename_cooldown_seconds" => {
+                api_config.rename_cooldown_seconds = value.parse().unwrap()
+            }
+            "require_ban_reason" => api_config.require_ban_reason = value.parse().unwrap(),
+            "ban_expiry_interval_seconds" => {
+                api_config.ban_expiry_interval_seconds = match value.parse().unwrap() {
+                    0 => panic!("Error: API Config | ban_expiry_interval_seconds Must Be Positive"),
+                    seconds => seconds,
+                }
+            }
+            "ban_removes_follow" => api_config.ban_removes_follow = value.parse().unwrap(),
+            "banned_cannot_follow" => api_config.banned_cannot_follow = value.parse().unwrap(),
+            "max_followed_count" => api_config.max_followed_count = value.parse().unwrap(),
+            "admin_token" => api_config.admin_token = value.to_string(),
+            "reserved_usernames" => {
+                api_config.username_rules.reserved = value
+                    .split(',')
+                    .map(|reserved| reserved.trim().to_string())
+                    .filter(|reserved| !reserved.is_empty())
+                    .collect()
+            }
+            _ => eprintln!("Error: API Config | Unknown Key {}", key),
+        }
+    }
+    api_config
+}